@@ -0,0 +1,46 @@
+use nalgebra::{Point3, Vector3};
+use cubotracer::materials::{opaque::Opaque, diffuse::Diffuse, specular::Specular};
+use cubotracer::camera::Camera;
+use cubotracer::camera::path::{CameraPath, CameraKeyframe, Interpolation};
+use cubotracer::world::World;
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::objects::plane::Plane;
+use cubotracer::objects::motion::Motion;
+use cubotracer::ray::Ray;
+
+fn background_color(ray: &Ray) -> Vector3<u8> {
+    let a: f32 = 0.5 * (ray.dir().y + 1.0);
+    let color = ((1.0 - a) * Vector3::<f32>::new(255.0, 255.0, 255.0)) + (a * Vector3::<f32>::new(127.0, 178.0, 255.0));
+    Vector3::<u8>::new(color.x as u8, color.y as u8, color.z as u8)
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+    let target = Point3::new(0.0, 0.0, -3.0);
+    let mut camera = Camera::new(Vector3::new(0.0, 1.0, 0.0), Vector3::new(1.0, 0.0, 0.0), 200, 0.873, 4.0 / 3.0, 3.0, 0.0);
+    camera.set_shutter(0.0, 0.1);
+    world.add_camera(camera, Point3::new(0.0, 0.5, 0.0), "camera".to_string());
+
+    // Turntable around the spheres that ends with a zoom in, passing smoothly through each keyframe
+    let keyframes = (0..5).map(|i| {
+        let angle = i as f32 * std::f32::consts::FRAC_PI_2;
+        let pos = target + Vector3::new(3.0 * angle.sin(), 0.5 + (0.5 * i as f32 / 4.0), 3.0 * angle.cos());
+        let vfov = if i == 4 { 0.5 } else { 0.873 };
+        CameraKeyframe::new(i as f32, pos, target, vfov, 3.0)
+    }).collect();
+    world.set_camera_path(CameraPath::new(keyframes, Interpolation::CatmullRom), "camera".to_string());
+
+    world.add_object(Box::new(Sphere::new(0.6)), Point3::new(0.7, -0.3, -2.9), Box::new(Diffuse::new(Vector3::<u8>::new(179, 77, 77), 0.5)), "dsphere".to_string());
+    world.add_object(Box::new(Sphere::new(0.5)), Point3::new(0.0, 0.6, -3.0), Box::new(Specular::new(Vector3::<u8>::new(204, 204, 204), 0.5)), "ssphere".to_string());
+    world.add_moving_object(
+        Box::new(Sphere::new(0.3)),
+        Point3::new(-1.5, -0.6, -3.0),
+        Motion::keyframed(vec![(0.0, Default::default()), (4.0, nalgebra::Isometry3::translation(3.0, 0.0, 0.0))]),
+        Box::new(Opaque::new(Vector3::<u8>::new(153, 50, 204))),
+        "osphere".to_string());
+    world.add_object(Box::new(Plane::new(Vector3::new(0.0, 1.0, 0.0))), Point3::new(0.0, -0.9, 0.0), Box::new(Diffuse::new(Vector3::<u8>::new(120, 160, 120), 0.5)), "floor".to_string());
+
+    // Two frames per unit of time
+    let paths = world.render_frames("camera".to_string(), 0..9, 2.0, 10, 5, "frame".to_string()).expect("Error writing rendered frames");
+    println!("Rendered {} frames", paths.len());
+}
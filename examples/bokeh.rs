@@ -0,0 +1,62 @@
+use nalgebra::{Point3, Vector3};
+use std::f32::consts::PI;
+use cubotracer::ppmhandler::{PPMHandler, PPMImage, PPMType};
+use cubotracer::materials::{opaque::Opaque, diffuse::Diffuse};
+use cubotracer::camera::Camera;
+use cubotracer::camera::aperture::Aperture;
+use cubotracer::world::World;
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::ray::Ray;
+
+fn background_color(_ray: &Ray) -> Vector3<u8> {
+    Vector3::new(10, 10, 20)
+}
+
+// Five pointed star drawn into a small image
+fn star_mask(size: usize) -> PPMImage {
+    let mut image = PPMImage::new(size, size, 255);
+    for x in 0..size {
+        for y in 0..size {
+            let (u, v) = ((2.0 * (x as f32 + 0.5) / size as f32) - 1.0, 1.0 - (2.0 * (y as f32 + 0.5) / size as f32));
+            let angle = u.atan2(v);
+            let spike = (((angle * 5.0 / (2.0 * PI)).fract() + 1.0).fract() - 0.5).abs() * 2.0;
+            if (u * u + v * v).sqrt() < 0.4 + (0.6 * (1.0 - spike)) {
+                image.change_pixel(x, y, Vector3::new(255, 255, 255));
+            }
+        }
+    }
+    image
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+    let up = Vector3::new(0.0, 1.0, 0.0);
+    let right = Vector3::new(1.0, 0.0, 0.0);
+
+    let apertures = [
+        ("bokeh-circular", Aperture::Circular),
+        ("bokeh-hexagon", Aperture::polygon(6, 0.0)),
+        ("bokeh-star", Aperture::mask(&star_mask(64))),
+    ];
+    for (key, aperture) in apertures {
+        let mut camera = Camera::new(up, right, 320, 0.6, 4.0 / 3.0, 2.0, 0.25);
+        camera.set_aperture(aperture);
+        world.add_camera(camera, Point3::new(0.0, 0.0, 0.0), key.to_string());
+    }
+
+    // Sphere in focus in front of a grid of small lights far behind it
+    world.add_object(Box::new(Sphere::new(0.3)), Point3::new(0.0, 0.0, -2.0), Box::new(Diffuse::new(Vector3::<u8>::new(179, 77, 77), 0.5)), "subject".to_string());
+    for i in 0..3 {
+        for j in 0..2 {
+            let center = Point3::new((i as f32 - 1.0) * 3.0, (j as f32 - 0.5) * 3.0, -8.0);
+            world.add_object(Box::new(Sphere::new(0.3)), center, Box::new(Opaque::new(Vector3::<u8>::new(255, 230, 160))), format!("light{}-{}", i, j));
+        }
+    }
+
+    let handler = PPMHandler();
+    for key in ["bokeh-circular", "bokeh-hexagon", "bokeh-star"] {
+        let result = world.render(key.to_string(), 10, 40);
+        handler.write(&result, PPMType::P6, key.to_string()).expect("Error writing rendered image");
+    }
+    println!("Render finished");
+}
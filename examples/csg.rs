@@ -0,0 +1,53 @@
+use nalgebra::{Point3, Vector3};
+use cubotracer::ppmhandler::{PPMHandler, PPMType};
+use cubotracer::materials::diffuse::Diffuse;
+use cubotracer::camera::Camera;
+use cubotracer::world::World;
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::objects::cylinder::Cylinder;
+use cubotracer::objects::csg::Csg;
+use cubotracer::objects::plane::Plane;
+use cubotracer::ray::Ray;
+
+fn background_color(ray: &Ray) -> Vector3<u8> {
+    let a: f32 = 0.5 * (ray.dir().y + 1.0);
+    let color = ((1.0 - a) * Vector3::<f32>::new(255.0, 255.0, 255.0)) + (a * Vector3::<f32>::new(127.0, 178.0, 255.0));
+    Vector3::<u8>::new(color.x as u8, color.y as u8, color.z as u8)
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+
+    world.add_camera(
+        Camera::new(
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            400,
+            0.873,
+            16.0 / 9.0,
+            3.4,
+            0.0,
+        ),
+        Point3::new(0.0, 0.8, 0.0),
+        "camera".to_string());
+    world.make_camera_lookat(Point3::new(0.0, 0.0, -3.0), "camera".to_string());
+
+    // Sphere with a cylindrical hole drilled through it
+    let drilled = Csg::difference(
+        Box::new(Sphere::new(0.6)), Vector3::zeros(),
+        Box::new(Cylinder::with_axis(0.25, 2.0, Vector3::new(0.0, 1.0, 1.0))), Vector3::zeros());
+    // Lens made from the overlap of two spheres
+    let lens = Csg::intersection(
+        Box::new(Sphere::new(0.6)), Vector3::new(-0.35, 0.0, 0.0),
+        Box::new(Sphere::new(0.6)), Vector3::new(0.35, 0.0, 0.0));
+
+    world.add_object(Box::new(drilled), Point3::new(-0.7, 0.1, -3.0), Box::new(Diffuse::new(Vector3::<u8>::new(179, 77, 77), 0.5)), "drilled".to_string());
+    world.add_object(Box::new(lens), Point3::new(0.8, 0.1, -3.0), Box::new(Diffuse::new(Vector3::<u8>::new(77, 77, 179), 0.5)), "lens".to_string());
+    world.add_object(Box::new(Plane::new(Vector3::new(0.0, 1.0, 0.0))), Point3::new(0.0, -0.5, 0.0), Box::new(Diffuse::new(Vector3::<u8>::new(120, 160, 120), 0.5)), "floor".to_string());
+
+    let result = world.render("camera".to_string(), 10, 5);
+    println!("Render finished");
+
+    let handler = PPMHandler();
+    handler.write(&result, PPMType::P6, "csg".to_string()).expect("Error writing rendered image");
+}
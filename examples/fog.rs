@@ -0,0 +1,50 @@
+use nalgebra::{Point3, Vector3};
+use cubotracer::ppmhandler::{PPMHandler, PPMType};
+use cubotracer::materials::{diffuse::Diffuse, specular::Specular};
+use cubotracer::media::homogeneous::Homogeneous;
+use cubotracer::media::phase::HenyeyGreenstein;
+use cubotracer::camera::Camera;
+use cubotracer::world::World;
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::objects::plane::Plane;
+use cubotracer::objects::sdf::Sdf;
+use cubotracer::objects::sdf::shapes::Cuboid;
+use cubotracer::ray::Ray;
+
+fn background_color(ray: &Ray) -> Vector3<u8> {
+    let a: f32 = 0.5 * (ray.dir().y + 1.0);
+    let color = ((1.0 - a) * Vector3::<f32>::new(255.0, 255.0, 255.0)) + (a * Vector3::<f32>::new(127.0, 178.0, 255.0));
+    Vector3::<u8>::new(color.x as u8, color.y as u8, color.z as u8)
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+
+    world.add_camera(
+        Camera::new(
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            400,
+            0.873,
+            16.0 / 9.0,
+            3.4,
+            0.0,
+        ),
+        Point3::new(0.0, 0.3, 0.0),
+        "camera".to_string());
+    world.make_camera_lookat(Point3::new(0.0, 0.0, -3.0), "camera".to_string());
+
+    world.add_object(Box::new(Sphere::new(0.4)), Point3::new(0.0, 0.0, -3.5), Box::new(Specular::new(Vector3::<u8>::new(204, 204, 204), 0.5)), "mirror".to_string());
+    world.add_object(Box::new(Plane::new(Vector3::new(0.0, 1.0, 0.0))), Point3::new(0.0, -0.4, 0.0), Box::new(Diffuse::new(Vector3::<u8>::new(120, 120, 120), 0.5)), "floor".to_string());
+
+    // Smoke sphere and smoke box with forward scattering, plus a thin haze over the whole scene
+    world.add_medium(Box::new(Sphere::new(0.4)), Point3::new(-1.0, 0.0, -3.0), Box::new(Homogeneous::new(4.0, 0.5, Vector3::new(230, 230, 230), HenyeyGreenstein::new(0.6))), "smoke".to_string());
+    world.add_medium(Box::new(Sdf::new(Box::new(Cuboid::new(Vector3::new(0.3, 0.4, 0.3))))), Point3::new(1.0, 0.0, -3.0), Box::new(Homogeneous::new(3.0, 1.0, Vector3::new(255, 140, 90), HenyeyGreenstein::isotropic())), "box".to_string());
+    world.set_fog(Box::new(Homogeneous::new(0.08, 0.0, Vector3::new(255, 255, 255), HenyeyGreenstein::isotropic())), 20.0);
+
+    let result = world.render("camera".to_string(), 10, 5);
+    println!("Render finished");
+
+    let handler = PPMHandler();
+    handler.write(&result, PPMType::P6, "fog".to_string()).expect("Error writing rendered image");
+}
@@ -0,0 +1,47 @@
+use nalgebra::{Point3, Vector3};
+use cubotracer::ppmhandler::{PPMHandler, PPMType};
+use cubotracer::materials::diffuse::Diffuse;
+use cubotracer::camera::Camera;
+use cubotracer::world::World;
+use cubotracer::objects::heightfield::Heightfield;
+use cubotracer::ray::Ray;
+
+fn background_color(ray: &Ray) -> Vector3<u8> {
+    let a: f32 = 0.5 * (ray.dir().y + 1.0);
+    let color = ((1.0 - a) * Vector3::<f32>::new(255.0, 255.0, 255.0)) + (a * Vector3::<f32>::new(127.0, 178.0, 255.0));
+    Vector3::<u8>::new(color.x as u8, color.y as u8, color.z as u8)
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+
+    world.add_camera(
+        Camera::new(
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            400,
+            0.873,
+            16.0 / 9.0,
+            3.4,
+            0.0,
+        ),
+        Point3::new(0.0, 1.5, 2.5),
+        "camera".to_string());
+    world.make_camera_lookat(Point3::new(0.0, 0.0, -1.0), "camera".to_string());
+
+    // Rolling hills, a greyscale image could be loaded instead with Heightfield::from_ppm
+    let samples = 128;
+    let heights: Vec<Vec<f32>> = (0..samples).map(|x| (0..samples).map(|z| {
+        let (u, v) = (x as f32 / samples as f32, z as f32 / samples as f32);
+        0.3 * ((u * 9.0).sin() * (v * 7.0).cos() + 1.0) + 0.1 * ((u * 31.0).sin() * (v * 23.0).sin())
+    }).collect()).collect();
+    let terrain = Heightfield::new(heights, 6.0, 6.0);
+
+    world.add_object(Box::new(terrain), Point3::new(0.0, -0.5, -1.5), Box::new(Diffuse::new(Vector3::<u8>::new(110, 150, 90), 0.5)), "terrain".to_string());
+
+    let result = world.render("camera".to_string(), 10, 5);
+    println!("Render finished");
+
+    let handler = PPMHandler();
+    handler.write(&result, PPMType::P6, "heightfield".to_string()).expect("Error writing rendered image");
+}
@@ -0,0 +1,44 @@
+use nalgebra::{Point3, Vector3};
+use cubotracer::ppmhandler::{PPMHandler, PPMType};
+use cubotracer::materials::{diffuse::Diffuse, specular::Specular, emissive::Emissive};
+use cubotracer::camera::Camera;
+use cubotracer::world::World;
+use cubotracer::integrators::Integrator;
+use cubotracer::integrators::{recursive::Recursive, whitted::Whitted, ambient_occlusion::AmbientOcclusion, path::PathTracer, debug::Debug};
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::objects::plane::Plane;
+use cubotracer::ray::Ray;
+
+fn background_color(ray: &Ray) -> Vector3<u8> {
+    let a: f32 = 0.5 * (ray.dir().y + 1.0);
+    let color = ((1.0 - a) * Vector3::<f32>::new(60.0, 60.0, 70.0)) + (a * Vector3::<f32>::new(20.0, 30.0, 60.0));
+    Vector3::<u8>::new(color.x as u8, color.y as u8, color.z as u8)
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+    world.add_camera(
+        Camera::new(Vector3::new(0.0, 1.0, 0.0), Vector3::new(1.0, 0.0, 0.0), 320, 0.873, 16.0 / 9.0, 3.0, 0.0),
+        Point3::new(0.0, 0.1, -0.5),
+        "camera".to_string());
+    world.add_object(Box::new(Sphere::new(0.6)), Point3::new(0.7, -0.3, -2.9), Box::new(Diffuse::new(Vector3::<u8>::new(179, 77, 77), 0.5)), "dsphere".to_string());
+    world.add_object(Box::new(Sphere::new(0.5)), Point3::new(-0.7, -0.4, -3.0), Box::new(Specular::new(Vector3::<u8>::new(204, 204, 204), 0.5)), "ssphere".to_string());
+    world.add_object(Box::new(Sphere::new(0.25)), Point3::new(-0.2, 1.0, -2.5), Box::new(Emissive::new(Vector3::<u8>::new(255, 240, 220), 40.0)), "light".to_string());
+    world.add_object(Box::new(Plane::new(Vector3::new(0.0, 1.0, 0.0))), Point3::new(0.0, -0.9, 0.0), Box::new(Diffuse::new(Vector3::<u8>::new(120, 160, 120), 0.5)), "floor".to_string());
+
+    // The same scene through each rendering algorithm
+    let integrators: Vec<(&str, Box<dyn Integrator>)> = vec![
+        ("recursive", Box::new(Recursive())),
+        ("whitted", Box::new(Whitted())),
+        ("ambient-occlusion", Box::new(AmbientOcclusion::new(1.0))),
+        ("path", Box::new(PathTracer::new(3))),
+        ("normals", Box::new(Debug::Normals)),
+        ("depth", Box::new(Debug::Depth(6.0))),
+    ];
+    let handler = PPMHandler();
+    for (name, integrator) in integrators {
+        let image = world.render_with("camera".to_string(), integrator.as_ref(), 20, 32);
+        handler.write(&image, PPMType::P6, format!("integrator-{}", name)).expect("Error writing rendered image");
+    }
+    println!("Render finished");
+}
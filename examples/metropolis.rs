@@ -0,0 +1,36 @@
+use nalgebra::{Point3, Vector3};
+use cubotracer::ppmhandler::{PPMHandler, PPMType};
+use cubotracer::materials::{diffuse::Diffuse, emissive::Emissive};
+use cubotracer::camera::Camera;
+use cubotracer::world::World;
+use cubotracer::integrators::{path::PathTracer, metropolis::Metropolis};
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::objects::plane::Plane;
+use cubotracer::objects::csg::Csg;
+use cubotracer::ray::Ray;
+
+fn background_color(_ray: &Ray) -> Vector3<u8> {
+    Vector3::<u8>::new(4, 4, 6)
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+    world.add_camera(
+        Camera::new(Vector3::new(0.0, 1.0, 0.0), Vector3::new(1.0, 0.0, 0.0), 320, 0.873, 16.0 / 9.0, 3.0, 0.0),
+        Point3::new(0.0, -0.1, -0.5),
+        "camera".to_string());
+    // A light shut inside a hollow ball, shining out onto the floor through a small hole underneath
+    let shell = Csg::difference(Box::new(Sphere::new(0.8)), Vector3::zeros(), Box::new(Sphere::new(0.75)), Vector3::zeros());
+    let ball = Csg::difference(Box::new(shell), Vector3::zeros(), Box::new(Sphere::new(0.25)), Vector3::new(0.0, -0.8, 0.0));
+    world.add_object(Box::new(ball), Point3::new(0.0, 0.3, -3.4), Box::new(Diffuse::new(Vector3::<u8>::new(200, 200, 200), 0.5)), "ball".to_string());
+    world.add_object(Box::new(Sphere::new(0.3)), Point3::new(0.0, 0.3, -3.4), Box::new(Emissive::new(Vector3::<u8>::new(255, 230, 200), 60.0)), "light".to_string());
+    world.add_object(Box::new(Plane::new(Vector3::new(0.0, 1.0, 0.0))), Point3::new(0.0, -0.9, 0.0), Box::new(Diffuse::new(Vector3::<u8>::new(160, 160, 200), 0.5)), "floor".to_string());
+
+    // About the same number of paths each, most of the path tracer's never find their way through the hole
+    let handler = PPMHandler();
+    let image = world.render_with("camera".to_string(), &PathTracer::new(3), 6, 64);
+    handler.write(&image, PPMType::P6, "metropolis-path".to_string()).expect("Error writing rendered image");
+    let image = world.render_metropolis("camera".to_string(), &Metropolis::new(100_000, 256, 64, 0.3, 0.01), 6);
+    handler.write(&image, PPMType::P6, "metropolis".to_string()).expect("Error writing rendered image");
+    println!("Render finished");
+}
@@ -0,0 +1,59 @@
+use nalgebra::{Isometry3, Point3, Vector3};
+use std::f32::consts::PI;
+use cubotracer::ppmhandler::{PPMHandler, PPMType};
+use cubotracer::materials::{diffuse::Diffuse, specular::Specular};
+use cubotracer::camera::Camera;
+use cubotracer::world::World;
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::objects::torus::Torus;
+use cubotracer::objects::plane::Plane;
+use cubotracer::objects::motion::Motion;
+use cubotracer::ray::Ray;
+
+fn background_color(ray: &Ray) -> Vector3<u8> {
+    let a: f32 = 0.5 * (ray.dir().y + 1.0);
+    let color = ((1.0 - a) * Vector3::<f32>::new(255.0, 255.0, 255.0)) + (a * Vector3::<f32>::new(127.0, 178.0, 255.0));
+    Vector3::<u8>::new(color.x as u8, color.y as u8, color.z as u8)
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+
+    let mut camera = Camera::new(
+        Vector3::new(0.0, 1.0, 0.0),
+        Vector3::new(1.0, 0.0, 0.0),
+        400,
+        0.873,
+        16.0 / 9.0,
+        3.4,
+        0.0,
+    );
+    camera.set_shutter(0.0, 1.0);
+    world.add_camera(camera, Point3::new(0.0, 0.1, -0.5), "camera".to_string());
+
+    // Ball rolling to the right while the shutter is open
+    world.add_moving_object(
+        Box::new(Sphere::new(0.4)),
+        Point3::new(-1.0, -0.1, -3.0),
+        Motion::translation(Vector3::new(0.6, 0.0, 0.0)),
+        Box::new(Diffuse::new(Vector3::<u8>::new(179, 77, 77), 0.5)),
+        "ball".to_string());
+    // Ring spinning about the vertical axis and bouncing up and back down
+    world.add_moving_object(
+        Box::new(Torus::with_axis(0.35, 0.1, Vector3::new(0.0, 0.0, 1.0))),
+        Point3::new(0.8, 0.0, -3.0),
+        Motion::keyframed(vec![
+            (0.0, Isometry3::new(Vector3::zeros(), Vector3::zeros())),
+            (0.5, Isometry3::new(Vector3::new(0.0, 0.2, 0.0), Vector3::new(0.0, PI / 4.0, 0.0))),
+            (1.0, Isometry3::new(Vector3::zeros(), Vector3::new(0.0, PI / 2.0, 0.0))),
+        ]),
+        Box::new(Specular::new(Vector3::<u8>::new(204, 204, 204), 0.5)),
+        "ring".to_string());
+    world.add_object(Box::new(Plane::new(Vector3::new(0.0, 1.0, 0.0))), Point3::new(0.0, -0.5, 0.0), Box::new(Diffuse::new(Vector3::<u8>::new(120, 160, 120), 0.5)), "floor".to_string());
+
+    let result = world.render("camera".to_string(), 10, 20);
+    println!("Render finished");
+
+    let handler = PPMHandler();
+    handler.write(&result, PPMType::P6, "motion-blur".to_string()).expect("Error writing rendered image");
+}
@@ -0,0 +1,42 @@
+use nalgebra::{Point3, Vector3};
+use std::f32::consts::PI;
+use cubotracer::ppmhandler::{PPMHandler, PPMType};
+use cubotracer::materials::{opaque::Opaque, diffuse::Diffuse, specular::Specular};
+use cubotracer::camera::Camera;
+use cubotracer::world::World;
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::objects::plane::Plane;
+use cubotracer::ray::Ray;
+
+fn background_color(ray: &Ray) -> Vector3<u8> {
+    let a: f32 = 0.5 * (ray.dir().y + 1.0);
+    let color = ((1.0 - a) * Vector3::<f32>::new(255.0, 255.0, 255.0)) + (a * Vector3::<f32>::new(127.0, 178.0, 255.0));
+    Vector3::<u8>::new(color.x as u8, color.y as u8, color.z as u8)
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+    let target = Point3::new(0.0, 0.0, -3.0);
+    world.add_camera(Camera::new(Vector3::new(0.0, 1.0, 0.0), Vector3::new(1.0, 0.0, 0.0), 200, 0.873, 4.0 / 3.0, 3.0, 0.0), Point3::new(0.0, 0.5, 0.0), "camera".to_string());
+    world.make_camera_lookat(target, "camera".to_string());
+
+    world.add_object(Box::new(Sphere::new(0.6)), Point3::new(0.7, -0.3, -2.9), Box::new(Diffuse::new(Vector3::<u8>::new(179, 77, 77), 0.5)), "dsphere".to_string());
+    world.add_object(Box::new(Sphere::new(0.5)), Point3::new(0.0, 0.6, -3.0), Box::new(Specular::new(Vector3::<u8>::new(204, 204, 204), 0.5)), "ssphere".to_string());
+    world.add_object(Box::new(Sphere::new(0.5)), Point3::new(-0.7, -0.3, -3.0), Box::new(Opaque::new(Vector3::<u8>::new(153, 50, 204))), "osphere".to_string());
+    world.add_object(Box::new(Plane::new(Vector3::new(0.0, 1.0, 0.0))), Point3::new(0.0, -0.9, 0.0), Box::new(Diffuse::new(Vector3::<u8>::new(120, 160, 120), 0.5)), "floor".to_string());
+
+    let handler = PPMHandler();
+    // Swing up and over the top of the spheres, then come back down to a closer, rolled view from the side
+    for frame in 0..4 {
+        let result = world.render("camera".to_string(), 10, 5);
+        handler.write(&result, PPMType::P6, format!("orbit-{}", frame)).expect("Error writing rendered image");
+        world.orbit_camera(target, 0.0, PI / 3.0, "camera".to_string());
+    }
+    world.orbit_camera(target, PI / 2.0, -PI / 2.0, "camera".to_string());
+    world.dolly_camera(1.0, "camera".to_string());
+    world.pan_camera(0.0, 0.3, "camera".to_string());
+    world.make_camera_lookat_with_up(target, Vector3::new(0.0, 1.0, 0.0), PI / 8.0, "camera".to_string());
+    let result = world.render("camera".to_string(), 10, 5);
+    handler.write(&result, PPMType::P6, "orbit-4".to_string()).expect("Error writing rendered image");
+    println!("Render finished");
+}
@@ -0,0 +1,43 @@
+use nalgebra::{Point3, Vector3};
+use cubotracer::ppmhandler::{PPMHandler, PPMType};
+use cubotracer::materials::{opaque::Opaque, diffuse::Diffuse, specular::Specular};
+use cubotracer::camera::Camera;
+use cubotracer::world::World;
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::objects::cylinder::Cylinder;
+use cubotracer::ray::Ray;
+
+fn background_color(ray: &Ray) -> Vector3<u8> {
+    let a: f32 = 0.5 * (ray.dir().y + 1.0);
+    let color = ((1.0 - a) * Vector3::<f32>::new(255.0, 255.0, 255.0)) + (a * Vector3::<f32>::new(127.0, 178.0, 255.0));
+    Vector3::<u8>::new(color.x as u8, color.y as u8, color.z as u8)
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+
+    // Parallel projection showing a 3 unit tall slice of the scene, so equal objects stay the same size at any depth
+    world.add_camera(
+        Camera::orthographic(
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            400,
+            3.0,
+            16.0 / 9.0,
+        ),
+        Point3::new(2.0, 1.5, 0.0),
+        "camera".to_string());
+    world.make_camera_lookat(Point3::new(0.0, 0.0, -4.0), "camera".to_string());
+
+    for (i, z) in [-3.0, -4.0, -5.0, -6.0].iter().enumerate() {
+        world.add_object(Box::new(Cylinder::new(0.3, 1.0)), Point3::new(-0.8, 0.0, *z), Box::new(Diffuse::new(Vector3::<u8>::new(179, 77, 77), 0.5)), format!("post{}", i));
+        world.add_object(Box::new(Sphere::new(0.3)), Point3::new(0.8, 0.0, *z), Box::new(Specular::new(Vector3::<u8>::new(204, 204, 204), 0.5)), format!("ball{}", i));
+    }
+    world.add_object(Box::new(Sphere::new(100.0)), Point3::new(0.0, -100.5, -4.0), Box::new(Opaque::new(Vector3::<u8>::new(90, 90, 90))), "ground".to_string());
+
+    let result = world.render("camera".to_string(), 10, 5);
+    println!("Render finished");
+
+    let handler = PPMHandler();
+    handler.write(&result, PPMType::P6, "orthographic".to_string()).expect("Error writing rendered image");
+}
@@ -0,0 +1,52 @@
+use nalgebra::{Point3, Vector3};
+use std::f32::consts::PI;
+use cubotracer::ppmhandler::{PPMHandler, PPMType};
+use cubotracer::materials::{opaque::Opaque, diffuse::Diffuse, specular::Specular};
+use cubotracer::camera::Camera;
+use cubotracer::camera::projection::FisheyeMapping;
+use cubotracer::world::World;
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::objects::plane::Plane;
+use cubotracer::ray::Ray;
+
+fn background_color(ray: &Ray) -> Vector3<u8> {
+    let a: f32 = 0.5 * (ray.dir().y + 1.0);
+    let color = ((1.0 - a) * Vector3::<f32>::new(255.0, 255.0, 255.0)) + (a * Vector3::<f32>::new(127.0, 178.0, 255.0));
+    Vector3::<u8>::new(color.x as u8, color.y as u8, color.z as u8)
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+    let up = Vector3::new(0.0, 1.0, 0.0);
+    let right = Vector3::new(1.0, 0.0, 0.0);
+    let pos = Point3::new(0.0, 0.0, 0.0);
+
+    world.add_camera(Camera::equirectangular(up, right, 512), pos, "equirectangular".to_string());
+    world.add_camera(Camera::fisheye(up, right, 256, PI, FisheyeMapping::Equidistant), pos, "fisheye".to_string());
+    world.add_camera(Camera::fisheye(up, right, 256, 1.2 * PI, FisheyeMapping::Equisolid), pos, "dome".to_string());
+    world.make_camera_lookat(Point3::new(0.0, 1.0, 0.0), "dome".to_string());
+
+    // Ring of spheres around the camera
+    for i in 0..8 {
+        let angle = i as f32 * PI / 4.0;
+        let center = Point3::new(2.0 * angle.sin(), 0.0, -2.0 * angle.cos());
+        let color = Vector3::new((128.0 + 120.0 * angle.cos()) as u8, (128.0 + 120.0 * angle.sin()) as u8, 180);
+        if i % 2 == 0 {
+            world.add_object(Box::new(Sphere::new(0.5)), center, Box::new(Diffuse::new(color, 0.5)), format!("sphere{}", i));
+        } else {
+            world.add_object(Box::new(Sphere::new(0.4)), center, Box::new(Specular::new(color, 0.5)), format!("sphere{}", i));
+        }
+    }
+    world.add_object(Box::new(Sphere::new(0.3)), Point3::new(0.0, 2.0, 0.0), Box::new(Opaque::new(Vector3::new(250, 220, 120))), "lamp".to_string());
+    world.add_object(Box::new(Plane::new(up)), Point3::new(0.0, -0.5, 0.0), Box::new(Diffuse::new(Vector3::<u8>::new(120, 160, 120), 0.5)), "floor".to_string());
+
+    let handler = PPMHandler();
+    for key in ["equirectangular", "fisheye", "dome"] {
+        let result = world.render(key.to_string(), 10, 5);
+        handler.write(&result, PPMType::P6, key.to_string()).expect("Error writing rendered image");
+    }
+    for (face, image) in world.render_cube_map("fisheye".to_string(), 10, 5) {
+        handler.write(&image, PPMType::P6, format!("cube-{:?}", face).to_lowercase()).expect("Error writing rendered image");
+    }
+    println!("Render finished");
+}
@@ -0,0 +1,38 @@
+use nalgebra::{Point3, Vector3};
+use cubotracer::ppmhandler::{PPMHandler, PPMType};
+use cubotracer::materials::{opaque::Opaque, diffuse::Diffuse, specular::Specular};
+use cubotracer::camera::Camera;
+use cubotracer::world::World;
+use cubotracer::integrators::path::PathTracer;
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::objects::plane::Plane;
+use cubotracer::ray::Ray;
+
+fn background_color(ray: &Ray) -> Vector3<u8> {
+    let a: f32 = 0.5 * (ray.dir().y + 1.0);
+    let color = ((1.0 - a) * Vector3::<f32>::new(255.0, 255.0, 255.0)) + (a * Vector3::<f32>::new(127.0, 178.0, 255.0));
+    Vector3::<u8>::new(color.x as u8, color.y as u8, color.z as u8)
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+    world.add_camera(
+        Camera::new(Vector3::new(0.0, 1.0, 0.0), Vector3::new(1.0, 0.0, 0.0), 400, 0.873, 16.0 / 9.0, 3.0, 0.0),
+        Point3::new(0.0, 0.1, -0.5),
+        "camera".to_string());
+    world.add_object(Box::new(Sphere::new(0.6)), Point3::new(0.7, -0.3, -2.9), Box::new(Diffuse::new(Vector3::<u8>::new(179, 77, 77), 0.5)), "dsphere".to_string());
+    world.add_object(Box::new(Sphere::new(0.5)), Point3::new(0.0, 0.6, -3.0), Box::new(Specular::new(Vector3::<u8>::new(204, 204, 204), 0.5)), "ssphere".to_string());
+    world.add_object(Box::new(Sphere::new(0.5)), Point3::new(-0.7, -0.3, -3.0), Box::new(Opaque::new(Vector3::<u8>::new(153, 50, 204))), "osphere".to_string());
+    world.add_object(Box::new(Plane::new(Vector3::new(0.0, 1.0, 0.0))), Point3::new(0.0, -0.9, 0.0), Box::new(Diffuse::new(Vector3::<u8>::new(120, 160, 120), 0.5)), "floor".to_string());
+
+    let handler = PPMHandler();
+    let legacy = world.render("camera".to_string(), 10, 64);
+    handler.write(&legacy, PPMType::P6, "legacy".to_string()).expect("Error writing rendered image");
+
+    // Surfaces now tint the light they bounce by their color, and long paths end by russian roulette
+    // after three bounces instead of all running to the bounce limit
+    world.set_integrator(Box::new(PathTracer::new(3)));
+    let path_traced = world.render("camera".to_string(), 50, 64);
+    handler.write(&path_traced, PPMType::P6, "path-traced".to_string()).expect("Error writing rendered image");
+    println!("Render finished");
+}
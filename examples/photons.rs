@@ -0,0 +1,35 @@
+use nalgebra::{Point3, Vector3};
+use cubotracer::ppmhandler::{PPMHandler, PPMType};
+use cubotracer::materials::{diffuse::Diffuse, dielectric::Dielectric, emissive::Emissive};
+use cubotracer::camera::Camera;
+use cubotracer::world::World;
+use cubotracer::integrators::photon::PhotonMapper;
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::objects::plane::Plane;
+use cubotracer::ray::Ray;
+
+fn background_color(_ray: &Ray) -> Vector3<u8> {
+    Vector3::new(8, 8, 12)
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+    let mut camera = Camera::new(Vector3::new(0.0, 1.0, 0.0), Vector3::new(1.0, 0.0, 0.0), 320, 0.8, 16.0 / 9.0, 3.0, 0.0);
+    camera.lookat(Point3::new(0.0, -0.5, -3.0), Point3::new(0.0, 0.8, 0.0));
+    world.add_camera(camera, Point3::new(0.0, 0.8, 0.0), "camera".to_string());
+    world.add_object(Box::new(Sphere::new(0.5)), Point3::new(0.0, -0.4, -3.0), Box::new(Dielectric::new(Vector3::<u8>::new(250, 250, 250), 1.5)), "glass".to_string());
+    world.add_object(Box::new(Sphere::new(0.15)), Point3::new(-1.2, 1.2, -3.8), Box::new(Emissive::new(Vector3::<u8>::new(255, 240, 220), 150.0)), "light".to_string());
+    world.add_object(Box::new(Plane::new(Vector3::new(0.0, 1.0, 0.0))), Point3::new(0.0, -0.9, 0.0), Box::new(Diffuse::new(Vector3::<u8>::new(200, 180, 150), 0.5)), "table".to_string());
+
+    // One photon map, blurred by its radius
+    let handler = PPMHandler();
+    let mapper = PhotonMapper::new(&world, 200000, 0.05, 10);
+    println!("Stored {} photons", mapper.photon_count());
+    let image = world.render_with("camera".to_string(), &mapper, 10, 16);
+    handler.write(&image, PPMType::P6, "photons".to_string()).expect("Error writing rendered image");
+
+    // A new photon map every pass with a shrinking radius, sharpening the caustic as passes are added
+    let film = world.render_progressive_photons("camera".to_string(), 10, 50000, 0.1, 2.0 / 3.0, 1, 32, |_, _| true);
+    handler.write(&film.to_image(), PPMType::P6, "photons-progressive".to_string()).expect("Error writing rendered image");
+    println!("Render finished");
+}
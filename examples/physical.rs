@@ -0,0 +1,49 @@
+use nalgebra::{Point3, Vector3};
+use cubotracer::ppmhandler::{PPMHandler, PPMType};
+use cubotracer::materials::{diffuse::Diffuse, specular::Specular};
+use cubotracer::camera::Camera;
+use cubotracer::world::World;
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::objects::plane::Plane;
+use cubotracer::ray::Ray;
+
+fn background_color(ray: &Ray) -> Vector3<u8> {
+    let a: f32 = 0.5 * (ray.dir().y + 1.0);
+    let color = ((1.0 - a) * Vector3::<f32>::new(255.0, 255.0, 255.0)) + (a * Vector3::<f32>::new(127.0, 178.0, 255.0));
+    Vector3::<u8>::new(color.x as u8, color.y as u8, color.z as u8)
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+    let up = Vector3::new(0.0, 1.0, 0.0);
+    let right = Vector3::new(1.0, 0.0, 0.0);
+
+    // 50mm lens wide open on a full frame sensor, distances are in meters
+    let camera = Camera::physical(up, right, 360, 50.0, 36.0, 24.0, 1.4, 1.0);
+    world.add_camera(camera, Point3::new(0.0, 0.0, 0.0), "physical".to_string());
+
+    // Row of spheres stepping away from the camera
+    for i in 0..5 {
+        let center = Point3::new(-0.6 + (0.3 * i as f32), 0.0, -2.0 - (1.5 * i as f32));
+        let color = Vector3::new(200 - (30 * i as u8), 80 + (30 * i as u8), 120);
+        if i % 2 == 0 {
+            world.add_object(Box::new(Sphere::new(0.25)), center, Box::new(Diffuse::new(color, 0.5)), format!("sphere{}", i));
+        } else {
+            world.add_object(Box::new(Sphere::new(0.25)), center, Box::new(Specular::new(color, 0.5)), format!("sphere{}", i));
+        }
+    }
+    world.add_object(Box::new(Plane::new(up)), Point3::new(0.0, -0.25, 0.0), Box::new(Diffuse::new(Vector3::<u8>::new(120, 160, 120), 0.5)), "floor".to_string());
+
+    let handler = PPMHandler();
+    // Focus on the middle sphere, which sits at the center of the image
+    let distance = world.autofocus_camera(180, 120, "physical".to_string()).expect("Nothing to focus on");
+    println!("Focused at {:.2}m", distance);
+    let result = world.render("physical".to_string(), 10, 20);
+    handler.write(&result, PPMType::P6, "physical-f1.4".to_string()).expect("Error writing rendered image");
+
+    // Stopping down keeps more of the row sharp
+    world.add_camera(Camera::physical(up, right, 360, 50.0, 36.0, 24.0, 8.0, distance), Point3::new(0.0, 0.0, 0.0), "physical".to_string());
+    let result = world.render("physical".to_string(), 10, 20);
+    handler.write(&result, PPMType::P6, "physical-f8".to_string()).expect("Error writing rendered image");
+    println!("Render finished");
+}
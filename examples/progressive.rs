@@ -0,0 +1,39 @@
+use nalgebra::{Point3, Vector3};
+use cubotracer::ppmhandler::{PPMHandler, PPMType};
+use cubotracer::materials::{opaque::Opaque, diffuse::Diffuse, specular::Specular};
+use cubotracer::camera::Camera;
+use cubotracer::world::World;
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::objects::plane::Plane;
+use cubotracer::ray::Ray;
+use std::time::{Duration, Instant};
+
+fn background_color(ray: &Ray) -> Vector3<u8> {
+    let a: f32 = 0.5 * (ray.dir().y + 1.0);
+    let color = ((1.0 - a) * Vector3::<f32>::new(255.0, 255.0, 255.0)) + (a * Vector3::<f32>::new(127.0, 178.0, 255.0));
+    Vector3::<u8>::new(color.x as u8, color.y as u8, color.z as u8)
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+    world.add_camera(
+        Camera::new(Vector3::new(0.0, 1.0, 0.0), Vector3::new(1.0, 0.0, 0.0), 320, 0.873, 16.0 / 9.0, 3.0, 0.0),
+        Point3::new(0.0, 0.1, -0.5),
+        "camera".to_string());
+    world.add_object(Box::new(Sphere::new(0.6)), Point3::new(0.7, -0.3, -2.9), Box::new(Diffuse::new(Vector3::<u8>::new(179, 77, 77), 0.5)), "dsphere".to_string());
+    world.add_object(Box::new(Sphere::new(0.5)), Point3::new(0.0, 0.6, -3.0), Box::new(Specular::new(Vector3::<u8>::new(204, 204, 204), 0.5)), "ssphere".to_string());
+    world.add_object(Box::new(Sphere::new(0.5)), Point3::new(-0.7, -0.3, -3.0), Box::new(Opaque::new(Vector3::<u8>::new(153, 50, 204))), "osphere".to_string());
+    world.add_object(Box::new(Plane::new(Vector3::new(0.0, 1.0, 0.0))), Point3::new(0.0, -0.9, 0.0), Box::new(Diffuse::new(Vector3::<u8>::new(120, 160, 120), 0.5)), "floor".to_string());
+
+    // Rewrites the image after every pass so it can be watched refining, and stops after a few seconds
+    let handler = PPMHandler();
+    let start = Instant::now();
+    let film = world.render_progressive("camera".to_string(), 10, 1, 256, |pass, film| {
+        handler.write(&film.to_image(), PPMType::P6, "progressive".to_string()).expect("Error writing rendered image");
+        println!("Pass {} written", pass);
+        start.elapsed() < Duration::from_secs(5)
+    });
+    let (width, height) = film.dim();
+    println!("Stopped at {} samples per pixel", film.total_count() / (width * height) as u64);
+    println!("Render finished");
+}
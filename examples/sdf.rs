@@ -0,0 +1,56 @@
+use nalgebra::{Point3, Vector3};
+use cubotracer::ppmhandler::{PPMHandler, PPMType};
+use cubotracer::materials::{diffuse::Diffuse, specular::Specular};
+use cubotracer::camera::Camera;
+use cubotracer::world::World;
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::objects::plane::Plane;
+use cubotracer::objects::sdf::Sdf;
+use cubotracer::objects::sdf::shapes::{self, Cuboid, Mandelbulb, Torus};
+use cubotracer::objects::sdf::operations::{Scale, Translate, Twist, Union};
+use cubotracer::ray::Ray;
+
+fn background_color(ray: &Ray) -> Vector3<u8> {
+    let a: f32 = 0.5 * (ray.dir().y + 1.0);
+    let color = ((1.0 - a) * Vector3::<f32>::new(255.0, 255.0, 255.0)) + (a * Vector3::<f32>::new(127.0, 178.0, 255.0));
+    Vector3::<u8>::new(color.x as u8, color.y as u8, color.z as u8)
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+
+    world.add_camera(
+        Camera::new(
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            400,
+            0.873,
+            16.0 / 9.0,
+            3.4,
+            0.0,
+        ),
+        Point3::new(0.0, 0.4, 0.0),
+        "camera".to_string());
+    world.make_camera_lookat(Point3::new(0.0, 0.0, -3.0), "camera".to_string());
+
+    // Organic blob from a sphere smoothly merged into a torus
+    let blob = Union::smooth(
+        Box::new(shapes::Sphere::new(0.3)),
+        Box::new(Translate::new(Box::new(Torus::new(0.35, 0.1)), Vector3::new(0.0, -0.2, 0.0))),
+        0.15);
+    // Twisted column, traced with shorter steps since twisting stretches the distance field
+    let column = Twist::new(Box::new(Cuboid::rounded(Vector3::new(0.15, 0.45, 0.15), 0.03)), 2.5);
+    let bulb = Scale::new(Box::new(Mandelbulb::new(8.0, 12)), 0.35);
+
+    world.add_object(Box::new(Sdf::new(Box::new(blob))), Point3::new(-1.0, 0.0, -3.0), Box::new(Diffuse::new(Vector3::<u8>::new(179, 77, 77), 0.5)), "blob".to_string());
+    world.add_object(Box::new(Sdf::with_limits(Box::new(column), 512, 100.0, 0.0001, 0.5)), Point3::new(0.0, 0.1, -3.2), Box::new(Specular::new(Vector3::<u8>::new(204, 204, 204), 0.5)), "column".to_string());
+    world.add_object(Box::new(Sdf::with_limits(Box::new(bulb), 512, 100.0, 0.001, 1.0)), Point3::new(1.0, 0.0, -3.0), Box::new(Diffuse::new(Vector3::<u8>::new(77, 77, 179), 0.5)), "bulb".to_string());
+    world.add_object(Box::new(Sphere::new(0.15)), Point3::new(0.5, -0.2, -2.5), Box::new(Diffuse::new(Vector3::<u8>::new(77, 179, 77), 0.5)), "sphere".to_string());
+    world.add_object(Box::new(Plane::new(Vector3::new(0.0, 1.0, 0.0))), Point3::new(0.0, -0.35, 0.0), Box::new(Diffuse::new(Vector3::<u8>::new(120, 120, 120), 0.5)), "floor".to_string());
+
+    let result = world.render("camera".to_string(), 10, 5);
+    println!("Render finished");
+
+    let handler = PPMHandler();
+    handler.write(&result, PPMType::P6, "sdf".to_string()).expect("Error writing rendered image");
+}
@@ -0,0 +1,45 @@
+use nalgebra::{Point3, Vector3};
+use cubotracer::ppmhandler::{PPMHandler, PPMImage, PPMType};
+use cubotracer::materials::{opaque::Opaque, diffuse::Diffuse, specular::Specular};
+use cubotracer::media::homogeneous::Homogeneous;
+use cubotracer::media::phase::HenyeyGreenstein;
+use cubotracer::camera::Camera;
+use cubotracer::world::World;
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::ray::Ray;
+
+fn background_color(ray: &Ray) -> Vector3<u8> {
+    let a: f32 = 0.5 * (ray.dir().y + 1.0);
+    let color = ((1.0 - a) * Vector3::<f32>::new(255.0, 255.0, 255.0)) + (a * Vector3::<f32>::new(127.0, 178.0, 255.0));
+    Vector3::<u8>::new(color.x as u8, color.y as u8, color.z as u8)
+}
+
+fn same_pixels(a: &PPMImage, b: &PPMImage) -> bool {
+    let (width, height) = a.dim();
+    a.dim() == b.dim() && (0..width).all(|x| (0..height).all(|y| a.pixel_at(x, y) == b.pixel_at(x, y)))
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+    world.add_camera(
+        Camera::new(Vector3::new(0.0, 1.0, 0.0), Vector3::new(1.0, 0.0, 0.0), 200, 0.873, 16.0 / 9.0, 3.4, 0.05),
+        Point3::new(0.0, 0.1, -0.5),
+        "camera".to_string());
+    world.add_object(Box::new(Sphere::new(0.6)), Point3::new(0.7, -0.3, -2.9), Box::new(Diffuse::new(Vector3::<u8>::new(179, 77, 77), 0.5)), "dsphere".to_string());
+    world.add_object(Box::new(Sphere::new(0.5)), Point3::new(0.0, 0.6, -3.0), Box::new(Specular::new(Vector3::<u8>::new(204, 204, 204), 0.5)), "ssphere".to_string());
+    world.add_object(Box::new(Sphere::new(0.5)), Point3::new(-0.7, -0.3, -3.0), Box::new(Opaque::new(Vector3::<u8>::new(153, 50, 204))), "osphere".to_string());
+    world.set_fog(Box::new(Homogeneous::new(0.05, 0.0, Vector3::new(230, 230, 230), HenyeyGreenstein::isotropic())), 10.0);
+
+    // Rendering twice with one seed gives the same image, which makes golden image tests possible
+    world.set_seed(42);
+    let first = world.render("camera".to_string(), 10, 5);
+    let second = world.render("camera".to_string(), 10, 5);
+    world.set_seed(7);
+    let other = world.render("camera".to_string(), 10, 5);
+    println!("Same seed matches: {}", same_pixels(&first, &second));
+    println!("Different seed matches: {}", same_pixels(&first, &other));
+
+    let handler = PPMHandler();
+    handler.write(&first, PPMType::P6, "seeded".to_string()).expect("Error writing rendered image");
+    println!("Render finished");
+}
@@ -0,0 +1,68 @@
+use nalgebra::{Point3, Vector3};
+use cubotracer::ppmhandler::{PPMHandler, PPMType};
+use cubotracer::materials::diffuse::Diffuse;
+use cubotracer::media::grid::{GridMedium, VoxelGrid};
+use cubotracer::media::phase::HenyeyGreenstein;
+use cubotracer::camera::Camera;
+use cubotracer::world::World;
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::objects::plane::Plane;
+use cubotracer::ray::Ray;
+
+fn background_color(ray: &Ray) -> Vector3<u8> {
+    let a: f32 = 0.5 * (ray.dir().y + 1.0);
+    let color = ((1.0 - a) * Vector3::<f32>::new(255.0, 255.0, 255.0)) + (a * Vector3::<f32>::new(127.0, 178.0, 255.0));
+    Vector3::<u8>::new(color.x as u8, color.y as u8, color.z as u8)
+}
+
+// Billowing plume that thins out towards the top, hottest near its base.
+// Grids can also be loaded with VoxelGrid::from_ascii or VoxelGrid::from_raw.
+fn plume(n: usize) -> (VoxelGrid, VoxelGrid) {
+    let mut density: Vec<f32> = vec![];
+    let mut temperature: Vec<f32> = vec![];
+    for z in 0..n {
+        for y in 0..n {
+            for x in 0..n {
+                let p = Vector3::new(x as f32, y as f32, z as f32) / n as f32 - Vector3::repeat(0.5);
+                let radius = 0.15 + (0.25 * (p.y + 0.5));
+                let swirl = 0.05 * ((p.y * 20.0).sin() + (p.x * 17.0).cos() * (p.z * 13.0).sin());
+                let r = (p.x.powi(2) + p.z.powi(2)).sqrt() + swirl;
+                let d = ((radius - r) / radius).clamp(0.0, 1.0) * (1.0 - (p.y + 0.5) * 0.6);
+                density.push(d);
+                temperature.push(if p.y < -0.2 { 1800.0 * d } else { 0.0 });
+            }
+        }
+    }
+    (VoxelGrid::new((n, n, n), density), VoxelGrid::new((n, n, n), temperature))
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+
+    world.add_camera(
+        Camera::new(
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            400,
+            0.873,
+            16.0 / 9.0,
+            3.4,
+            0.0,
+        ),
+        Point3::new(0.0, 0.3, 0.0),
+        "camera".to_string());
+    world.make_camera_lookat(Point3::new(0.0, 0.2, -3.0), "camera".to_string());
+
+    world.add_object(Box::new(Plane::new(Vector3::new(0.0, 1.0, 0.0))), Point3::new(0.0, -0.5, 0.0), Box::new(Diffuse::new(Vector3::<u8>::new(120, 120, 120), 0.5)), "floor".to_string());
+
+    let (density, temperature) = plume(48);
+    let smoke = GridMedium::with_temperature(density, temperature, 40000.0, Vector3::new(1.2, 1.2, 1.2), 12.0, 4.0, Vector3::new(220, 220, 220), HenyeyGreenstein::new(0.3));
+    // The boundary only needs to enclose the grid, anything outside the grid has no density
+    world.add_medium(Box::new(Sphere::new(1.05)), Point3::new(0.0, 0.1, -3.0), Box::new(smoke), "plume".to_string());
+
+    let result = world.render("camera".to_string(), 10, 5);
+    println!("Render finished");
+
+    let handler = PPMHandler();
+    handler.write(&result, PPMType::P6, "smoke".to_string()).expect("Error writing rendered image");
+}
@@ -0,0 +1,54 @@
+use nalgebra::{Point3, Vector3};
+use cubotracer::ppmhandler::{PPMHandler, PPMType};
+use cubotracer::materials::{emissive::Emissive, dielectric::Dielectric};
+use cubotracer::camera::Camera;
+use cubotracer::world::World;
+use cubotracer::integrators::{path::PathTracer, spectral::SpectralPathTracer};
+use cubotracer::objects::Object;
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::objects::plane::Plane;
+use cubotracer::objects::csg::Csg;
+use cubotracer::ray::Ray;
+
+fn background_color(_ray: &Ray) -> Vector3<u8> {
+    Vector3::<u8>::new(10, 10, 14)
+}
+
+// Glass prism lying along the x axis, its triangular ends facing the sides
+fn prism(length: f32, side: f32) -> Box<dyn Object> {
+    let height = side * 3.0_f32.sqrt() / 2.0;
+    let slope = |z: f32| Vector3::new(0.0, 0.5, z * 3.0_f32.sqrt() / 2.0);
+    let body = Csg::intersection(
+        Box::new(Plane::new(Vector3::new(0.0, -1.0, 0.0))), Vector3::new(0.0, -height / 3.0, 0.0),
+        Box::new(Csg::intersection(
+            Box::new(Plane::new(slope(1.0))), Vector3::new(0.0, height / 6.0, side / 4.0),
+            Box::new(Plane::new(slope(-1.0))), Vector3::new(0.0, height / 6.0, -side / 4.0))), Vector3::zeros());
+    let ends = Csg::intersection(
+        Box::new(Plane::new(Vector3::new(1.0, 0.0, 0.0))), Vector3::new(length / 2.0, 0.0, 0.0),
+        Box::new(Plane::new(Vector3::new(-1.0, 0.0, 0.0))), Vector3::new(-length / 2.0, 0.0, 0.0));
+    Box::new(Csg::intersection(Box::new(body), Vector3::zeros(), Box::new(ends), Vector3::zeros()))
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+    world.add_camera(
+        Camera::new(Vector3::new(0.0, 1.0, 0.0), Vector3::new(1.0, 0.0, 0.0), 320, 0.873, 16.0 / 9.0, 3.0, 0.0),
+        Point3::new(0.0, 0.0, -0.5),
+        "camera".to_string());
+    // Flint glass bends blue noticeably more than red, so the lights seen through it are spread into rainbows
+    world.add_object(prism(2.4, 1.0), Point3::new(0.0, 0.0, -3.0), Box::new(Dielectric::with_dispersion(Vector3::<u8>::new(255, 255, 255), 1.6, 0.03)), "prism".to_string());
+    for (i, x) in [-1.8, -0.6, 0.6, 1.8].into_iter().enumerate() {
+        for (j, y) in [-2.8, -2.0, 0.9].into_iter().enumerate() {
+            let light = Box::new(Emissive::new(Vector3::<u8>::new(255, 255, 255), 1.0));
+            world.add_object(Box::new(Sphere::new(0.3)), Point3::new(x, y, -6.0), light, format!("light{}{}", i, j));
+        }
+    }
+
+    // Side by side, the RGB path tracer bends all colors alike and shows plain white lights
+    let handler = PPMHandler();
+    let image = world.render_with("camera".to_string(), &PathTracer::new(4), 10, 64);
+    handler.write(&image, PPMType::P6, "spectral-rgb".to_string()).expect("Error writing rendered image");
+    let image = world.render_with("camera".to_string(), &SpectralPathTracer::new(4), 10, 64);
+    handler.write(&image, PPMType::P6, "spectral".to_string()).expect("Error writing rendered image");
+    println!("Render finished");
+}
@@ -0,0 +1,53 @@
+use nalgebra::{Point3, Vector3};
+use std::f32::consts::PI;
+use cubotracer::ppmhandler::{PPMHandler, PPMType};
+use cubotracer::materials::{opaque::Opaque, diffuse::Diffuse, specular::Specular};
+use cubotracer::camera::Camera;
+use cubotracer::camera::stereo::{Stereo, Convergence, StereoLayout};
+use cubotracer::world::World;
+use cubotracer::objects::sphere::Sphere;
+use cubotracer::objects::plane::Plane;
+use cubotracer::ray::Ray;
+
+fn background_color(ray: &Ray) -> Vector3<u8> {
+    let a: f32 = 0.5 * (ray.dir().y + 1.0);
+    let color = ((1.0 - a) * Vector3::<f32>::new(255.0, 255.0, 255.0)) + (a * Vector3::<f32>::new(127.0, 178.0, 255.0));
+    Vector3::<u8>::new(color.x as u8, color.y as u8, color.z as u8)
+}
+
+fn main() {
+    let mut world = World::new(Box::new(background_color));
+    let up = Vector3::new(0.0, 1.0, 0.0);
+    let right = Vector3::new(1.0, 0.0, 0.0);
+    let pos = Point3::new(0.0, 0.1, 0.0);
+
+    // Eyes 6.4cm apart with the views lining up at the middle sphere
+    let mut off_axis = Camera::new(up, right, 320, 0.873, 4.0 / 3.0, 3.0, 0.0);
+    off_axis.set_stereo(Stereo::new(0.064, 3.0, Convergence::OffAxis, StereoLayout::SideBySide));
+    world.add_camera(off_axis, pos, "stereo-off-axis".to_string());
+
+    let mut toe_in = Camera::new(up, right, 320, 0.873, 4.0 / 3.0, 3.0, 0.0);
+    toe_in.set_stereo(Stereo::new(0.064, 3.0, Convergence::ToeIn, StereoLayout::TopBottom));
+    world.add_camera(toe_in, pos, "stereo-toe-in".to_string());
+
+    let mut panorama = Camera::equirectangular(up, right, 512);
+    panorama.set_stereo(Stereo::new(0.064, f32::INFINITY, Convergence::OffAxis, StereoLayout::TopBottom));
+    world.add_camera(panorama, pos, "stereo-panorama".to_string());
+
+    world.add_object(Box::new(Sphere::new(0.5)), Point3::new(-0.8, -0.1, -2.0), Box::new(Diffuse::new(Vector3::<u8>::new(179, 77, 77), 0.5)), "near".to_string());
+    world.add_object(Box::new(Sphere::new(0.5)), Point3::new(0.0, 0.1, -3.0), Box::new(Specular::new(Vector3::<u8>::new(204, 204, 204), 0.5)), "middle".to_string());
+    world.add_object(Box::new(Sphere::new(0.5)), Point3::new(0.9, 0.2, -4.5), Box::new(Opaque::new(Vector3::<u8>::new(153, 50, 204))), "far".to_string());
+    for i in 0..6 {
+        let angle = (i as f32 * PI / 3.0) + (PI / 6.0);
+        let center = Point3::new(2.5 * angle.sin(), -0.1, 2.5 * angle.cos());
+        world.add_object(Box::new(Sphere::new(0.4)), center, Box::new(Diffuse::new(Vector3::<u8>::new(77, 128, 179), 0.5)), format!("ring{}", i));
+    }
+    world.add_object(Box::new(Plane::new(up)), Point3::new(0.0, -0.5, 0.0), Box::new(Diffuse::new(Vector3::<u8>::new(120, 160, 120), 0.5)), "floor".to_string());
+
+    let handler = PPMHandler();
+    for key in ["stereo-off-axis", "stereo-toe-in", "stereo-panorama"] {
+        let result = world.render(key.to_string(), 10, 5);
+        handler.write(&result, PPMType::P6, key.to_string()).expect("Error writing rendered image");
+    }
+    println!("Render finished");
+}
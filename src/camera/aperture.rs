@@ -0,0 +1,139 @@
+use std::f32::consts::PI;
+use crate::ppmhandler::{PPMHandler, PPMImage};
+use crate::sampler::SampleStream;
+
+// Shape of the lens opening, which gives out of focus highlights their shape. Shapes fit within the
+// unit disk and are scaled by the defocus radius of the camera.
+#[derive(Clone)]
+pub enum Aperture {
+    Circular,
+    Polygon { blades: u32, rotation: f32 }, // Regular polygon with a corner straight up when rotation is 0
+    Mask(ApertureMask),
+}
+
+impl Aperture {
+    pub fn polygon(blades: u32, rotation: f32) -> Self {
+        if blades < 3 {
+            panic!("Polygonal aperture needs at least 3 blades, given {}", blades)
+        }
+        Aperture::Polygon { blades, rotation }
+    }
+
+    pub fn mask(image: &PPMImage) -> Self {
+        Aperture::Mask(ApertureMask::new(image))
+    }
+
+    pub fn from_ppm(path: String) -> std::io::Result<Self> {
+        let image = PPMHandler().read(&path)?;
+        Ok(Self::mask(&image))
+    }
+
+    // Samples a point on the aperture as (right, up)
+    pub fn sample(&self, samples: &mut SampleStream) -> (f32, f32) {
+        let (u1, u2) = samples.next_2d();
+        self.sample_with(u1, u2)
+    }
+
+    pub fn sample_with(&self, u1: f32, u2: f32) -> (f32, f32) {
+        match self {
+            Aperture::Circular => {
+                let r = u1.sqrt();
+                let theta = 2.0 * PI * u2;
+                (r * theta.cos(), r * theta.sin())
+            },
+            Aperture::Polygon { blades, rotation } => {
+                // Pick one of the triangles between the center and each edge, then a point within it
+                let scaled = u1 * *blades as f32;
+                let blade = scaled.floor().min((blades - 1) as f32);
+                let u1 = scaled - blade;
+                let corner = |i: f32| {
+                    let angle = rotation + (2.0 * PI * i / *blades as f32);
+                    (angle.sin(), angle.cos())
+                };
+                let (a, b) = (corner(blade), corner(blade + 1.0));
+                let s = u1.sqrt();
+                ((s * (1.0 - u2) * a.0) + (s * u2 * b.0), (s * (1.0 - u2) * a.1) + (s * u2 * b.1))
+            },
+            Aperture::Mask(mask) => mask.sample_with(u1, u2),
+        }
+    }
+}
+
+// Aperture shaped by the brightness of an image, with brighter pixels letting through more light. The
+// image is centered on the lens with its longer side spanning the diameter of the unit disk, and is cut to
+// that disk so corners of the image that reach past it let no light through.
+#[derive(Clone)]
+pub struct ApertureMask {
+    dim: (usize, usize),
+    cdf: Vec<f32>, // Running total of pixel weights, row by row from the top left
+}
+
+impl ApertureMask {
+    pub fn new(image: &PPMImage) -> Self {
+        let (width, height) = image.dim();
+        let mut cdf: Vec<f32> = Vec::with_capacity(width * height);
+        let mut total = 0.0;
+        for y in 0..height {
+            for x in 0..width {
+                let (cx, cy) = to_disk((width, height), x as f32 + 0.5, y as f32 + 0.5);
+                if cx.powi(2) + cy.powi(2) <= 1.0 {
+                    let pixel = image.pixel_at(x, y);
+                    total += (pixel.x + pixel.y + pixel.z) as f32 / (3.0 * image.maxval() as f32);
+                }
+                cdf.push(total);
+            }
+        }
+        if total <= 0.0 {
+            panic!("Aperture mask must have at least one pixel within the unit disk that isn't black")
+        }
+        Self {
+            dim: (width, height),
+            cdf,
+        }
+    }
+
+    fn sample_with(&self, u1: f32, u2: f32) -> (f32, f32) {
+        let total = self.cdf[self.cdf.len() - 1];
+        let target = u1 * total;
+        let index = self.cdf.partition_point(|c| *c <= target).min(self.cdf.len() - 1);
+        // Reuse where u1 fell within the chosen pixel to place the point vertically
+        let start = if index == 0 { 0.0 } else { self.cdf[index - 1] };
+        let v = ((target - start) / (self.cdf[index] - start)).clamp(0.0, 1.0);
+        let (x, y) = (index % self.dim.0, index / self.dim.0);
+        let (px, py) = to_disk(self.dim, x as f32 + u2, y as f32 + v);
+        // Pixels on the edge of the disk can stick out a little past it
+        let r = (px.powi(2) + py.powi(2)).sqrt();
+        if r > 1.0 { (px / r, py / r) } else { (px, py) }
+    }
+}
+
+// Point on the lens of a position in pixels on a mask image of the given size
+fn to_disk((width, height): (usize, usize), x: f32, y: f32) -> (f32, f32) {
+    let scale = 2.0 / width.max(height) as f32;
+    ((x - (width as f32 / 2.0)) * scale, ((height as f32 / 2.0) - y) * scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Vector3;
+
+    #[test]
+    fn samples_stay_within_the_unit_disk() {
+        let mut image = PPMImage::new(8, 5, 255);
+        for x in 0..8 {
+            for y in 0..5 {
+                image.change_pixel(x, y, Vector3::repeat(255));
+            }
+        }
+        let apertures = [Aperture::Circular, Aperture::polygon(5, 0.3), Aperture::mask(&image)];
+        for aperture in apertures.iter() {
+            for i in 0..64 {
+                for j in 0..64 {
+                    let (x, y) = aperture.sample_with((i as f32 + 0.5) / 64.0, (j as f32 + 0.5) / 64.0);
+                    assert!(x.powi(2) + y.powi(2) <= 1.0 + 1e-5, "Sampled ({}, {}) outside the unit disk", x, y);
+                }
+            }
+        }
+    }
+}
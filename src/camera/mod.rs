@@ -0,0 +1,492 @@
+use nalgebra::{Vector3, Point3, Unit, UnitQuaternion};
+use std::f32::consts::PI;
+use std::ops::Range;
+use crate::ray::Ray;
+use crate::sampler::{Sampler, SampleStream};
+
+pub mod projection;
+pub mod stereo;
+pub mod aperture;
+pub mod path;
+use aperture::Aperture;
+use projection::{Projection, CubeFace, FisheyeMapping};
+use stereo::{Stereo, Convergence, Eye};
+
+const MM_PER_UNIT: f32 = 1000.0;
+const EPS: f32 = 0.00001;
+
+// Where a camera ray starts, given as a position on the image in pixels from its top left corner,
+// a point on the lens in [0, 1]^2 and the time it is sent at
+#[derive(Copy, Clone, Debug)]
+pub struct CameraSample {
+    pub film: (f32, f32),
+    pub lens: (f32, f32),
+    pub time: f32,
+}
+
+#[derive(Clone)]
+pub struct Camera {
+    projection: Projection,
+    up: Vector3<f32>,
+    right: Vector3<f32>,
+    dir: Vector3<f32>,
+    iwidth: usize,
+    iheight: usize,
+    vheight: f32,
+    vwidth: f32,
+    fdist: f32, // Distance to the view plane, which is also the distance the camera is focused at
+    lens_radius: f32,
+    lens: Option<(f32, f32)>, // (focal length in mm, f-number) of cameras with physical settings
+    aperture: Aperture,
+    shutter: (f32, f32), // Rays are sent at times spread evenly over the interval the shutter is open
+    stereo: Option<Stereo>, // Renders both eyes into one image when set
+}
+
+impl Camera {
+    pub fn new(up: Vector3<f32>, right: Vector3<f32>, iwidth: usize, vfov: f32, aratio: f32, fdist: f32, defangle: f32) -> Self {
+        let iheight: usize = if iwidth as f32 / aratio < 1.0 { 1 } else { (iwidth as f32 / aratio) as usize };
+        let vheight: f32 = 2.0 * (vfov / 2.0).tan() * fdist;
+        Self {
+            projection: Projection::Perspective,
+            up: up.normalize(),
+            right: right.normalize(),
+            dir: up.cross(&right).normalize(),
+            iwidth,
+            iheight,
+            vheight,
+            vwidth: vheight * (iwidth as f32 / iheight as f32), 
+            fdist,
+            lens_radius: fdist * (defangle / 2.0).tan(),
+            lens: None,
+            aperture: Aperture::Circular,
+            shutter: (0.0, 0.0),
+            stereo: None,
+        }
+    }
+
+    // Physical Camera Requirement
+    // Camera described by a lens of focal_length mm on a sensor of sensor_width by sensor_height mm. World
+    // units are taken to be meters, so the lens opening of focal_length / f_number mm is scaled to match.
+    #[allow(clippy::too_many_arguments)]
+    pub fn physical(up: Vector3<f32>, right: Vector3<f32>, iwidth: usize, focal_length: f32, sensor_width: f32, sensor_height: f32, f_number: f32, focus_distance: f32) -> Self {
+        if focal_length <= 0.0 || sensor_width <= 0.0 || sensor_height <= 0.0 {
+            panic!("Focal length and sensor size must be positive, given focal length {} and sensor {} x {}", focal_length, sensor_width, sensor_height)
+        }
+        let vfov = 2.0 * (sensor_height / (2.0 * focal_length)).atan();
+        let mut camera = Self::new(up, right, iwidth, vfov, sensor_width / sensor_height, focus_distance, 0.0);
+        camera.lens = Some((focal_length, f_number));
+        camera.set_f_number(f_number);
+        camera
+    }
+
+    // Moves the focus without changing the field of view or the size of the lens opening
+    pub fn set_focus_distance(&mut self, focus_distance: f32) {
+        if focus_distance <= 0.0 {
+            panic!("Focus distance must be positive, given distance was {}", focus_distance)
+        }
+        if self.projection == Projection::Perspective {
+            self.vheight *= focus_distance / self.fdist;
+            self.vwidth *= focus_distance / self.fdist;
+        }
+        self.fdist = focus_distance;
+    }
+
+    pub fn get_focus_distance(&self) -> f32 {
+        self.fdist
+    }
+
+    // Changes the vertical field of view of a perspective camera, zooming the lens of physical cameras to match
+    pub fn set_vfov(&mut self, vfov: f32) {
+        if self.projection != Projection::Perspective {
+            panic!("Only perspective cameras have a field of view to set")
+        } else if vfov <= 0.0 || PI <= vfov {
+            panic!("Field of view must be between 0 and pi, given field of view was {}", vfov)
+        }
+        let scale = (vfov / 2.0).tan() / (self.get_vfov() / 2.0).tan();
+        match self.lens {
+            Some((focal_length, _)) => self.set_focal_length(focal_length / scale),
+            None => {
+                self.vheight *= scale;
+                self.vwidth *= scale;
+            },
+        }
+    }
+
+    pub fn get_vfov(&self) -> f32 {
+        2.0 * (self.vheight / (2.0 * self.fdist)).atan()
+    }
+
+    pub fn set_f_number(&mut self, f_number: f32) {
+        if f_number <= 0.0 {
+            panic!("F-number must be positive, given f-number was {}", f_number)
+        }
+        match self.lens {
+            Some((focal_length, _)) => {
+                self.lens = Some((focal_length, f_number));
+                self.lens_radius = focal_length / f_number / 2.0 / MM_PER_UNIT;
+            },
+            None => panic!("Camera has no focal length, create it with Camera::physical to set an f-number"),
+        }
+    }
+
+    // Zooms the lens, keeping the sensor size and f-number
+    pub fn set_focal_length(&mut self, focal_length: f32) {
+        if focal_length <= 0.0 {
+            panic!("Focal length must be positive, given focal length was {}", focal_length)
+        }
+        match self.lens {
+            Some((old, f_number)) => {
+                self.vheight *= old / focal_length;
+                self.vwidth *= old / focal_length;
+                self.lens = Some((focal_length, f_number));
+                self.set_f_number(f_number);
+            },
+            None => panic!("Camera has no focal length, create it with Camera::physical to set one"),
+        }
+    }
+
+    pub fn get_focal_length(&self) -> Option<f32> {
+        self.lens.map(|(focal_length, _)| focal_length)
+    }
+
+    pub fn get_f_number(&self) -> Option<f32> {
+        self.lens.map(|(_, f_number)| f_number)
+    }
+
+    // Orthographic Requirement
+    // vheight is the height of the view plane in world units, the width follows from the aspect ratio
+    pub fn orthographic(up: Vector3<f32>, right: Vector3<f32>, iwidth: usize, vheight: f32, aratio: f32) -> Self {
+        let mut camera = Self::new(up, right, iwidth, 0.0, aratio, 1.0, 0.0);
+        camera.projection = Projection::Orthographic;
+        camera.vheight = vheight;
+        camera.vwidth = vheight * (camera.iwidth as f32 / camera.iheight as f32);
+        camera
+    }
+
+    // Panoramic Requirement
+    // Covers every direction, images should be twice as wide as they are tall
+    pub fn equirectangular(up: Vector3<f32>, right: Vector3<f32>, iwidth: usize) -> Self {
+        Self::panoramic(up, right, iwidth, 2.0, Projection::Equirectangular)
+    }
+
+    // Square image with the view direction at its center, fov is the angle across the image circle
+    pub fn fisheye(up: Vector3<f32>, right: Vector3<f32>, iwidth: usize, fov: f32, mapping: FisheyeMapping) -> Self {
+        if fov <= 0.0 || 2.0 * PI < fov {
+            panic!("Fisheye field of view must be between 0 and 2 pi, given field of view was {}", fov)
+        }
+        Self::panoramic(up, right, iwidth, 1.0, Projection::Fisheye { fov, mapping })
+    }
+
+    pub fn cube_face(up: Vector3<f32>, right: Vector3<f32>, iwidth: usize, face: CubeFace) -> Self {
+        Self::panoramic(up, right, iwidth, 1.0, Projection::CubeFace(face))
+    }
+
+    fn panoramic(up: Vector3<f32>, right: Vector3<f32>, iwidth: usize, aratio: f32, projection: Projection) -> Self {
+        let mut camera = Self::new(up, right, iwidth, PI / 2.0, aratio, 1.0, 0.0);
+        camera.projection = projection;
+        camera
+    }
+
+    // Copy of this camera rendering one face of a cube map, keeping its width and orientation
+    pub fn with_cube_face(&self, face: CubeFace) -> Self {
+        let mut camera = Self::cube_face(self.up, self.right, self.iwidth, face);
+        camera.shutter = self.shutter;
+        camera.stereo = self.stereo;
+        camera
+    }
+
+    pub fn get_projection(&self) -> Projection {
+        self.projection
+    }
+
+    pub fn get_dir(&self) -> Vector3<f32> {
+        self.dir
+    }
+
+    pub fn get_up(&self) -> Vector3<f32> {
+        self.up
+    }
+
+    pub fn get_right(&self) -> Vector3<f32> {
+        self.right
+    }
+
+    // Width of the rendered image, which holds both eyes for stereo cameras
+    pub fn get_iwidth(&self) -> usize {
+        self.stereo.map_or(self.iwidth, |stereo| stereo.combined_size(self.iwidth, self.iheight).0)
+    }
+
+    pub fn get_iheight(&self) -> usize {
+        self.stereo.map_or(self.iheight, |stereo| stereo.combined_size(self.iwidth, self.iheight).1)
+    }
+
+    // Bokeh Requirement
+    pub fn set_aperture(&mut self, aperture: Aperture) {
+        self.aperture = aperture;
+    }
+
+    pub fn get_aperture(&self) -> &Aperture {
+        &self.aperture
+    }
+
+    // Motion Blur Requirement
+    pub fn set_shutter(&mut self, open: f32, close: f32) {
+        if close < open {
+            panic!("Shutter must open before it closes, given interval was [{}, {}]", open, close)
+        }
+        self.shutter = (open, close);
+    }
+
+    pub fn get_shutter(&self) -> (f32, f32) {
+        self.shutter
+    }
+
+    // Stereo Requirement
+    pub fn set_stereo(&mut self, stereo: Stereo) {
+        self.stereo = Some(stereo);
+    }
+
+    pub fn clear_stereo(&mut self) {
+        self.stereo = None;
+    }
+
+    pub fn get_stereo(&self) -> Option<Stereo> {
+        self.stereo
+    }
+
+    // Antialiasing Requirement
+    // Returns rays_per_pixel rays for every pixel along with the samples left for rendering each of them.
+    // Rays are made one at a time as the iterator is advanced, so memory use doesn't grow with the sample count.
+    pub fn get_rays<'a>(&'a self, pos: &'a Point3<f32>, rays_per_pixel: u32, sampler: &'a dyn Sampler, seed: u32) -> impl Iterator<Item = (Ray, usize, usize, SampleStream<'a>)> + 'a {
+        (0..self.get_iwidth())
+            .flat_map(move |x| (0..self.get_iheight()).map(move |y| (x, y)))
+            .flat_map(move |(x, y)| self.get_pixel_rays(pos, (x, y), rays_per_pixel, sampler, seed).map(move |(ray, samples)| (ray, x, y, samples)))
+    }
+
+    // Returns the rays_per_pixel rays of one pixel, made lazily like get_rays
+    pub fn get_pixel_rays<'a>(&'a self, pos: &'a Point3<f32>, pixel: (usize, usize), rays_per_pixel: u32, sampler: &'a dyn Sampler, seed: u32) -> impl Iterator<Item = (Ray, SampleStream<'a>)> + 'a {
+        (0..rays_per_pixel).filter_map(move |index| self.get_ray(pos, pixel, index, rays_per_pixel, sampler, seed))
+    }
+
+    // Splits the image into tiles of at most tile_size by tile_size pixels, going along rows of tiles from the
+    // top left. Each tile is given as its range of columns and range of rows.
+    pub fn get_tiles(&self, tile_size: usize) -> impl Iterator<Item = (Range<usize>, Range<usize>)> {
+        if tile_size == 0 {
+            panic!("Tiles must be at least 1 pixel wide")
+        }
+        let (width, height) = (self.get_iwidth(), self.get_iheight());
+        (0..height).step_by(tile_size)
+            .flat_map(move |y| (0..width).step_by(tile_size).map(move |x| (x..(x + tile_size).min(width), y..(y + tile_size).min(height))))
+    }
+
+    // Ray for sample index out of count samples of a pixel. The ray takes the position within its pixel, the
+    // point on the lens and the time from its samples, and the rest are returned for rendering it.
+    pub fn get_ray<'a>(&self, pos: &Point3<f32>, pixel: (usize, usize), index: u32, count: u32, sampler: &'a dyn Sampler, seed: u32) -> Option<(Ray, SampleStream<'a>)> {
+        let mut samples = SampleStream::new(sampler, pixel, index, count, seed);
+        let (jx, jy) = samples.next_2d();
+        let sample = CameraSample {
+            film: (pixel.0 as f32 + jx, pixel.1 as f32 + jy),
+            lens: samples.next_2d(),
+            time: self.shutter.0 + ((self.shutter.1 - self.shutter.0) * samples.next_1d()),
+        };
+        self.ray_for_sample(pos, &sample).map(|ray| (ray, samples))
+    }
+
+    // Light Tracing Requirement
+    // Connects a point in the world to the camera through a point on the lens picked from lens, returning the
+    // point on the lens, the position on the image in pixels and the camera's importance along that line. The
+    // importance is spread evenly over the image, so it adds up to 1 across all pixels. Only perspective
+    // cameras without stereo can be connected to, and points outside the view give None.
+    pub fn connect(&self, pos: &Point3<f32>, point: &Point3<f32>, lens: (f32, f32)) -> Option<(Point3<f32>, (f32, f32), f32)> {
+        if self.projection != Projection::Perspective || self.stereo.is_some() {
+            return None
+        }
+        let lens_point = self.ray_origin(pos, &self.right, lens);
+        let dir = (point - lens_point).normalize();
+        let cos = dir.dot(&self.dir);
+        if cos <= EPS {
+            return None
+        }
+        // Rays from every point on the lens meet on the view plane, which is at the focus distance
+        let image_topleft = pos + (self.fdist * self.dir) + (self.up * self.vheight / 2.0) - (self.right * self.vwidth / 2.0);
+        let offset = (lens_point + (dir * self.fdist / cos)) - image_topleft;
+        let (px, py) = (offset.dot(&self.right) * self.iwidth as f32 / self.vwidth, -offset.dot(&self.up) * self.iheight as f32 / self.vheight);
+        if px < 0.0 || py < 0.0 || px >= self.iwidth as f32 || py >= self.iheight as f32 {
+            return None
+        }
+        Some((lens_point, (px, py), self.direction_pdf(&dir) / cos))
+    }
+
+    // Probability density per unit of solid angle of the camera sending a ray along dir, 0 for cameras connect doesn't support
+    pub fn direction_pdf(&self, dir: &Vector3<f32>) -> f32 {
+        if self.projection != Projection::Perspective || self.stereo.is_some() {
+            return 0.0
+        }
+        let cos = dir.normalize().dot(&self.dir);
+        if cos <= EPS {
+            return 0.0
+        }
+        // Area of the image on a plane at distance 1 from the lens
+        let area = self.vwidth * self.vheight / self.fdist.powi(2);
+        1.0 / (area * cos.powi(3))
+    }
+
+    // Ray through the center of a pixel from the center of the lens, used to focus on what the pixel sees
+    pub fn focus_ray(&self, pos: &Point3<f32>, x: usize, y: usize) -> Option<Ray> {
+        let pinhole = Self { lens_radius: 0.0, ..self.clone() };
+        pinhole.ray_for_sample(pos, &CameraSample { film: (x as f32 + 0.5, y as f32 + 0.5), lens: (0.5, 0.5), time: self.shutter.0 })
+    }
+
+    // Returns the ray through a position on the image given in pixels from its top left corner,
+    // or None if the projection doesn't cover that position
+    fn ray_for_sample(&self, pos: &Point3<f32>, sample: &CameraSample) -> Option<Ray> {
+        match self.stereo {
+            Some(stereo) => {
+                let (eye, px, py) = stereo.split(sample.film.0, sample.film.1, self.iwidth, self.iheight);
+                self.eye_ray(pos, &stereo, eye, &CameraSample { film: (px, py), ..*sample })
+            },
+            None => self.view_ray(pos, &self.dir, &self.right, 0.0, sample),
+        }
+    }
+
+    // Ray from a view with the camera's up vector and the given direction and right vectors, with the view
+    // plane shifted along right by shift
+    fn view_ray(&self, pos: &Point3<f32>, dir: &Vector3<f32>, right: &Vector3<f32>, shift: f32, sample: &CameraSample) -> Option<Ray> {
+        let (px, py) = sample.film;
+        match self.projection {
+            Projection::Perspective | Projection::Orthographic => {
+                let image_topleft = pos + (self.fdist * dir) + (self.up * self.vheight / 2.0) - (right * ((self.vwidth / 2.0) - shift));
+                let psample = image_topleft + (px * self.vwidth / self.iwidth as f32 * right) - (py * self.vheight / self.iheight as f32 * self.up);
+                let ori = if self.projection == Projection::Perspective {
+                    self.ray_origin(pos, right, sample.lens)
+                } else {
+                    psample - (self.fdist * dir)
+                };
+                Some(Ray::with_time(ori, psample - ori, sample.time))
+            },
+            _ => {
+                let local = self.projection.direction(px / self.iwidth as f32, py / self.iheight as f32)?;
+                Some(Ray::with_time(*pos, (local.x * right) + (local.y * self.up) + (local.z * dir), sample.time))
+            },
+        }
+    }
+
+    fn eye_ray(&self, pos: &Point3<f32>, stereo: &Stereo, eye: Eye, sample: &CameraSample) -> Option<Ray> {
+        let offset = stereo.eye_offset(eye);
+        let distance = stereo.get_convergence_distance();
+        match self.projection {
+            Projection::Perspective | Projection::Orthographic => {
+                let eye_pos = pos + (offset * self.right);
+                match stereo.get_convergence() {
+                    Convergence::ToeIn => {
+                        let dir = converge(&self.dir, &(offset * self.right), distance);
+                        self.view_ray(&eye_pos, &dir, &dir.cross(&self.up).normalize(), 0.0, sample)
+                    },
+                    // Shifted so both view planes cover the same area at the convergence distance
+                    Convergence::OffAxis => self.view_ray(&eye_pos, &self.dir, &self.right, -offset * self.fdist / distance, sample),
+                }
+            },
+            _ => {
+                // Omni-directional stereo, the eye offset shrinks towards the poles to keep them seamless
+                let dir = self.view_ray(pos, &self.dir, &self.right, 0.0, sample)?.dir();
+                let eye_offset = offset * dir.cross(&self.up);
+                Some(Ray::with_time(pos + eye_offset, converge(&dir, &eye_offset, distance), sample.time))
+            },
+        }
+    }
+
+    // Depth of Field Requirement
+    fn ray_origin(&self, pos: &Point3<f32>, right: &Vector3<f32>, lens: (f32, f32)) -> Point3<f32> {
+        let (x, y) = self.aperture.sample_with(lens.0, lens.1);
+        pos + (self.lens_radius * ((x * right) + (y * self.up)))
+    }
+
+    // Camera Requirement
+    pub fn lookat(&mut self, target: Point3<f32>, pos: Point3<f32>) {
+        self.lookat_with_up(target, pos, Vector3::new(0.0, 1.0, 0.0), 0.0);
+    }
+
+    // Points the camera at target keeping its up vector as close to up as possible, then rolls it by roll radians
+    pub fn lookat_with_up(&mut self, target: Point3<f32>, pos: Point3<f32>, up: Vector3<f32>, roll: f32) {
+        self.look_along(target - pos, up, roll);
+    }
+
+    // Looking along a direction parallel to up falls back on the current orientation to pick the right vector,
+    // so the camera can turn to look straight up or down from anywhere. Directions of zero length are ignored.
+    pub fn look_along(&mut self, dir: Vector3<f32>, up: Vector3<f32>, roll: f32) {
+        if dir.magnitude() < EPS {
+            return;
+        }
+        let dir = dir.normalize();
+        let right = [dir.cross(&up), dir.cross(&self.up), self.right - (self.right.dot(&dir) * dir)]
+            .into_iter()
+            .find(|right| right.magnitude() > EPS)
+            .unwrap()
+            .normalize();
+        self.dir = dir;
+        self.right = right;
+        self.up = right.cross(&dir);
+        self.rotate(UnitQuaternion::from_axis_angle(&Unit::new_normalize(-dir), roll));
+    }
+
+    pub fn rotate(&mut self, rotation: UnitQuaternion<f32>) {
+        self.dir = (rotation * self.dir).normalize();
+        self.up = (rotation * self.up).normalize();
+        self.right = (rotation * self.right).normalize();
+    }
+
+    // Orientation Requirement
+    // Yaw turns the camera left about the world y axis starting from looking along -z, pitch tilts it up and
+    // roll turns it counterclockwise about its view direction as seen from behind, all in radians
+    pub fn set_yaw_pitch_roll(&mut self, yaw: f32, pitch: f32, roll: f32) {
+        let rotation = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), yaw)
+            * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), pitch)
+            * UnitQuaternion::from_axis_angle(&Vector3::z_axis(), roll);
+        self.right = rotation * Vector3::x();
+        self.up = rotation * Vector3::y();
+        self.dir = rotation * -Vector3::z();
+    }
+
+    pub fn set_yaw(&mut self, yaw: f32) {
+        self.set_yaw_pitch_roll(yaw, self.get_pitch(), self.get_roll());
+    }
+
+    pub fn set_pitch(&mut self, pitch: f32) {
+        self.set_yaw_pitch_roll(self.get_yaw(), pitch, self.get_roll());
+    }
+
+    pub fn set_roll(&mut self, roll: f32) {
+        self.set_yaw_pitch_roll(self.get_yaw(), self.get_pitch(), roll);
+    }
+
+    // Looking straight up or down, yaw is taken from the right vector and roll is 0
+    pub fn get_yaw(&self) -> f32 {
+        if 1.0 - self.dir.y.abs() < EPS {
+            (-self.right.z).atan2(self.right.x)
+        } else {
+            (-self.dir.x).atan2(-self.dir.z)
+        }
+    }
+
+    pub fn get_pitch(&self) -> f32 {
+        self.dir.y.clamp(-1.0, 1.0).asin()
+    }
+
+    pub fn get_roll(&self) -> f32 {
+        if 1.0 - self.dir.y.abs() < EPS {
+            return 0.0
+        }
+        let level_right = Vector3::new(0.0, 1.0, 0.0).cross(&-self.dir).normalize();
+        let level_up = (-self.dir).cross(&level_right);
+        self.right.dot(&level_up).atan2(self.right.dot(&level_right))
+    }
+}
+
+// Direction from an eye at offset from the center of the rig towards the point at distance along dir
+fn converge(dir: &Vector3<f32>, offset: &Vector3<f32>, distance: f32) -> Vector3<f32> {
+    if distance.is_infinite() {
+        *dir
+    } else {
+        ((distance * dir) - offset).normalize()
+    }
+}
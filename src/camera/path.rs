@@ -0,0 +1,115 @@
+use nalgebra::Point3;
+use std::f32::consts::PI;
+use std::ops::{Add, Sub, Mul};
+use crate::camera::Camera;
+use crate::camera::projection::Projection;
+
+const EPS: f32 = 0.0001;
+
+// How a camera path moves between keyframes
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Interpolation {
+    Linear,
+    CatmullRom, // Smooth curve passing through every keyframe
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct CameraKeyframe {
+    pub time: f32,
+    pub pos: Point3<f32>,
+    pub target: Point3<f32>,
+    pub vfov: f32, // Only used by perspective cameras
+    pub focus_distance: f32,
+}
+
+impl CameraKeyframe {
+    pub fn new(time: f32, pos: Point3<f32>, target: Point3<f32>, vfov: f32, focus_distance: f32) -> Self {
+        Self {
+            time,
+            pos,
+            target,
+            vfov,
+            focus_distance,
+        }
+    }
+
+    // Points the camera at the target from pos and sets its field of view and focus
+    pub fn apply(&self, camera: &mut Camera) {
+        camera.lookat(self.target, self.pos);
+        if camera.get_projection() == Projection::Perspective {
+            camera.set_vfov(self.vfov);
+        }
+        camera.set_focus_distance(self.focus_distance);
+    }
+}
+
+// Keyframed position, target, field of view and focus of a camera. The camera holds its first and
+// last keyframes before and after the path.
+pub struct CameraPath {
+    keyframes: Vec<CameraKeyframe>,
+    interpolation: Interpolation,
+}
+
+impl CameraPath {
+    pub fn new(mut keyframes: Vec<CameraKeyframe>, interpolation: Interpolation) -> Self {
+        if keyframes.is_empty() {
+            panic!("Camera path needs at least one keyframe")
+        }
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self {
+            keyframes,
+            interpolation,
+        }
+    }
+
+    pub fn get_interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    pub fn keyframe_at(&self, time: f32) -> CameraKeyframe {
+        let first = self.keyframes[0];
+        let last = self.keyframes[self.keyframes.len() - 1];
+        if time <= first.time {
+            return CameraKeyframe { time, ..first }
+        } else if time >= last.time {
+            return CameraKeyframe { time, ..last }
+        }
+        let next = self.keyframes.iter().position(|k| k.time > time).unwrap();
+        let (k1, k2) = (self.keyframes[next - 1], self.keyframes[next]);
+        let s = (time - k1.time) / (k2.time - k1.time);
+        match self.interpolation {
+            Interpolation::Linear => CameraKeyframe {
+                time,
+                pos: k1.pos + (s * (k2.pos - k1.pos)),
+                target: k1.target + (s * (k2.target - k1.target)),
+                vfov: k1.vfov + (s * (k2.vfov - k1.vfov)),
+                focus_distance: k1.focus_distance + (s * (k2.focus_distance - k1.focus_distance)),
+            },
+            Interpolation::CatmullRom => {
+                // End segments reuse their end keyframe as the missing neighbour
+                let k0 = self.keyframes[next.saturating_sub(2)];
+                let k3 = self.keyframes[(next + 1).min(self.keyframes.len() - 1)];
+                let point = |f: fn(&CameraKeyframe) -> Point3<f32>| Point3::from(catmull_rom(f(&k0).coords, f(&k1).coords, f(&k2).coords, f(&k3).coords, s));
+                let scalar = |f: fn(&CameraKeyframe) -> f32| catmull_rom(f(&k0), f(&k1), f(&k2), f(&k3), s);
+                // Overshooting the keyframes must not give an impossible lens
+                CameraKeyframe {
+                    time,
+                    pos: point(|k| k.pos),
+                    target: point(|k| k.target),
+                    vfov: scalar(|k| k.vfov).clamp(EPS, PI - EPS),
+                    focus_distance: scalar(|k| k.focus_distance).max(EPS),
+                }
+            },
+        }
+    }
+}
+
+// Uniform Catmull-Rom spline between p1 and p2 at s in [0, 1]
+fn catmull_rom<T>(p0: T, p1: T, p2: T, p3: T, s: f32) -> T
+where T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f32, Output = T> {
+    let (s2, s3) = (s * s, s * s * s);
+    ((p1 * 2.0)
+        + ((p2 - p0) * s)
+        + (((p0 * 2.0) - (p1 * 5.0) + (p2 * 4.0) - p3) * s2)
+        + (((p1 * 3.0) - p0 - (p2 * 3.0) + p3) * s3)) * 0.5
+}
@@ -0,0 +1,85 @@
+use nalgebra::Vector3;
+use std::f32::consts::PI;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Projection {
+    Perspective, // Rays spread out from the camera position through the view plane
+    Orthographic, // Rays travel parallel to the view direction from points on the view plane
+    Equirectangular, // Longitude across the image and latitude down it, covering every direction
+    CubeFace(CubeFace), // One 90 degree face of a cube map around the camera
+    Fisheye { fov: f32, mapping: FisheyeMapping }, // Circular image with the view direction at its center
+}
+
+// Faces of a cube map, relative to the orientation of the camera
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CubeFace {
+    Front,
+    Back,
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl CubeFace {
+    pub fn all() -> [CubeFace; 6] {
+        [CubeFace::Front, CubeFace::Back, CubeFace::Left, CubeFace::Right, CubeFace::Up, CubeFace::Down]
+    }
+
+    // (right, up, forward) of the face in camera space, where the camera looks along +z with +y up
+    fn basis(&self) -> (Vector3<f32>, Vector3<f32>, Vector3<f32>) {
+        let x = Vector3::new(1.0, 0.0, 0.0);
+        let y = Vector3::new(0.0, 1.0, 0.0);
+        let z = Vector3::new(0.0, 0.0, 1.0);
+        match self {
+            CubeFace::Front => (x, y, z),
+            CubeFace::Back => (-x, y, -z),
+            CubeFace::Left => (z, y, -x),
+            CubeFace::Right => (-z, y, x),
+            CubeFace::Up => (x, -z, y),
+            CubeFace::Down => (x, z, -y),
+        }
+    }
+}
+
+// How the angle from the view direction maps to the distance from the center of a fisheye image
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FisheyeMapping {
+    Equidistant, // Distance proportional to the angle
+    Equisolid, // Equal areas in the image cover equal solid angles
+}
+
+impl Projection {
+    // Returns the direction in camera space (x right, y up, z forward) for a position (u, v) on the image,
+    // with u going left to right and v top to bottom over [0, 1]. Returns None for positions the
+    // projection doesn't cover, and for projections that don't send rays from a single point.
+    pub fn direction(&self, u: f32, v: f32) -> Option<Vector3<f32>> {
+        match self {
+            Projection::Perspective | Projection::Orthographic => None,
+            Projection::Equirectangular => {
+                let longitude = (u - 0.5) * 2.0 * PI;
+                let latitude = (0.5 - v) * PI;
+                Some(Vector3::new(latitude.cos() * longitude.sin(), latitude.sin(), latitude.cos() * longitude.cos()))
+            },
+            Projection::CubeFace(face) => {
+                let (right, up, forward) = face.basis();
+                Some((forward + ((2.0 * u - 1.0) * right) - ((2.0 * v - 1.0) * up)).normalize())
+            },
+            Projection::Fisheye { fov, mapping } => {
+                let (x, y) = ((2.0 * u) - 1.0, 1.0 - (2.0 * v));
+                let r = (x.powi(2) + y.powi(2)).sqrt();
+                if r > 1.0 {
+                    return None
+                }
+                let theta = match mapping {
+                    FisheyeMapping::Equidistant => r * fov / 2.0,
+                    FisheyeMapping::Equisolid => 2.0 * (r * (fov / 4.0).sin()).asin(),
+                };
+                if r == 0.0 {
+                    return Some(Vector3::new(0.0, 0.0, 1.0))
+                }
+                Some(Vector3::new(theta.sin() * x / r, theta.sin() * y / r, theta.cos()))
+            },
+        }
+    }
+}
@@ -0,0 +1,88 @@
+// How the two eyes of a stereo camera are aimed at the convergence distance
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Convergence {
+    ToeIn, // Each eye turns inward to look at the convergence point
+    OffAxis, // Eyes look in parallel and their view planes are shifted to overlap at the convergence distance
+}
+
+// Where the two eye images go in the combined image
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum StereoLayout {
+    SideBySide, // Left eye on the left, right eye on the right
+    TopBottom, // Left eye on top, right eye on the bottom
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Eye {
+    Left,
+    Right,
+}
+
+// Settings for rendering a camera as a pair of eyes. Panoramic projections use omni-directional stereo,
+// where the eyes sit on a circle with the interocular distance as its diameter and turn with each
+// direction around the up axis, so every column of the panorama sees the scene in stereo.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Stereo {
+    interocular: f32,
+    convergence_distance: f32, // Distance where the eyes' views line up, may be infinite for parallel eyes
+    convergence: Convergence,
+    layout: StereoLayout,
+}
+
+impl Stereo {
+    pub fn new(interocular: f32, convergence_distance: f32, convergence: Convergence, layout: StereoLayout) -> Self {
+        if interocular < 0.0 {
+            panic!("Interocular distance must be non-negative, given distance was {}", interocular)
+        }
+        if convergence_distance <= 0.0 {
+            panic!("Convergence distance must be positive, given distance was {}", convergence_distance)
+        }
+        Self {
+            interocular,
+            convergence_distance,
+            convergence,
+            layout,
+        }
+    }
+
+    pub fn get_interocular(&self) -> f32 {
+        self.interocular
+    }
+
+    pub fn get_convergence_distance(&self) -> f32 {
+        self.convergence_distance
+    }
+
+    pub fn get_convergence(&self) -> Convergence {
+        self.convergence
+    }
+
+    pub fn get_layout(&self) -> StereoLayout {
+        self.layout
+    }
+
+    // Size of the combined image holding both eyes
+    pub fn combined_size(&self, iwidth: usize, iheight: usize) -> (usize, usize) {
+        match self.layout {
+            StereoLayout::SideBySide => (2 * iwidth, iheight),
+            StereoLayout::TopBottom => (iwidth, 2 * iheight),
+        }
+    }
+
+    // Splits a position on the combined image into the eye it belongs to and the position on that eye's image
+    pub fn split(&self, px: f32, py: f32, iwidth: usize, iheight: usize) -> (Eye, f32, f32) {
+        match self.layout {
+            StereoLayout::SideBySide if px >= iwidth as f32 => (Eye::Right, px - iwidth as f32, py),
+            StereoLayout::TopBottom if py >= iheight as f32 => (Eye::Right, px, py - iheight as f32),
+            _ => (Eye::Left, px, py),
+        }
+    }
+
+    // Signed distance of an eye from the center of the rig along the right vector
+    pub fn eye_offset(&self, eye: Eye) -> f32 {
+        match eye {
+            Eye::Left => -self.interocular / 2.0,
+            Eye::Right => self.interocular / 2.0,
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use nalgebra::Vector3;
+use crate::ppmhandler::PPMImage;
+
+// Running totals of the samples of one pixel
+#[derive(Copy, Clone)]
+struct PixelStats {
+    sum: Vector3<f32>,
+    sum_squares: f32, // Of brightness, for estimating noise
+    count: u32,
+}
+
+// Accumulates color samples for every pixel of an image, keeping enough of each pixel's history to
+// tell how noisy its average still is. Colors are on the same 0 to 255 scale as images.
+pub struct Film {
+    dim: (usize, usize),
+    pixels: Vec<Vec<PixelStats>>, // vec of columns, pixels[x][y]
+    splats: Vec<Vec<Vector3<f32>>>, // Sums of light left on each pixel by samples of other pixels
+    total: u64, // Samples added across all pixels
+}
+
+impl Film {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            dim: (width, height),
+            pixels: vec![vec![PixelStats { sum: Vector3::zeros(), sum_squares: 0.0, count: 0 }; height]; width],
+            splats: vec![vec![Vector3::zeros(); height]; width],
+            total: 0,
+        }
+    }
+
+    pub fn dim(&self) -> (usize, usize) {
+        self.dim
+    }
+
+    // Samples that came out NaN or infinite, like from a path with a zero pdf, count as black so that one bad
+    // sample can't spoil the pixel
+    pub fn add_sample(&mut self, x: usize, y: usize, color: Vector3<f32>) {
+        let color = if is_finite(&color) { color } else { Vector3::zeros() };
+        let pixel = &mut self.pixels[x][y];
+        pixel.sum += color;
+        pixel.sum_squares += brightness(&color).powi(2);
+        pixel.count += 1;
+        self.total += 1;
+    }
+
+    // Light Tracing Requirement
+    // Adds light a sample of any pixel left on this one. Every sample may leave light anywhere, so splats are
+    // averaged over all samples of the film rather than the pixel's own.
+    pub fn add_splat(&mut self, x: usize, y: usize, color: Vector3<f32>) {
+        if is_finite(&color) {
+            self.splats[x][y] += color;
+        }
+    }
+
+    pub fn count(&self, x: usize, y: usize) -> u32 {
+        self.pixels[x][y].count
+    }
+
+    pub fn total_count(&self) -> u64 {
+        self.total
+    }
+
+    // Average of the samples of a pixel plus its share of the splats
+    pub fn mean(&self, x: usize, y: usize) -> Vector3<f32> {
+        let pixel = &self.pixels[x][y];
+        let splat = if self.total == 0 {
+            Vector3::zeros()
+        } else {
+            self.splats[x][y] * (self.dim.0 * self.dim.1) as f32 / self.total as f32
+        };
+        if pixel.count == 0 {
+            return splat
+        }
+        (pixel.sum / pixel.count as f32) + splat
+    }
+
+    // Sample variance of the brightness of a pixel, infinite until it has two samples
+    pub fn variance(&self, x: usize, y: usize) -> f32 {
+        let pixel = &self.pixels[x][y];
+        if pixel.count < 2 {
+            return f32::INFINITY
+        }
+        let n = pixel.count as f32;
+        let mean = brightness(&pixel.sum) / n;
+        ((pixel.sum_squares - (n * mean * mean)) / (n - 1.0)).max(0.0)
+    }
+
+    // Half the width of the 95% confidence interval of a pixel's average brightness
+    pub fn error(&self, x: usize, y: usize) -> f32 {
+        1.96 * (self.variance(x, y) / self.count(x, y) as f32).sqrt()
+    }
+
+    // Image of the average of every pixel, pixels without samples are black
+    pub fn to_image(&self) -> PPMImage {
+        let mut image = PPMImage::new(self.dim.0, self.dim.1, 255);
+        for x in 0..self.dim.0 {
+            for y in 0..self.dim.1 {
+                image.change_pixel(x, y, self.mean(x, y).map(|c| if c.is_nan() { 0.0 } else { c.clamp(0.0, 255.0) }).try_cast::<u32>().unwrap());
+            }
+        }
+        image
+    }
+
+    // Grayscale image of how many samples each pixel got, with the most sampled pixels white
+    pub fn count_image(&self) -> PPMImage {
+        let most = self.pixels.iter().flatten().map(|pixel| pixel.count).max().unwrap_or(0).max(1);
+        let mut image = PPMImage::new(self.dim.0, self.dim.1, 255);
+        for x in 0..self.dim.0 {
+            for y in 0..self.dim.1 {
+                image.change_pixel(x, y, Vector3::repeat(255 * self.count(x, y) / most));
+            }
+        }
+        image
+    }
+}
+
+fn brightness(color: &Vector3<f32>) -> f32 {
+    (color.x + color.y + color.z) / 3.0
+}
+
+// Whether every channel of a color is a number and not infinite
+pub(crate) fn is_finite(color: &Vector3<f32>) -> bool {
+    color.iter().all(|c| c.is_finite())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_finite_samples_count_as_black() {
+        let mut film = Film::new(2, 1);
+        film.add_sample(0, 0, Vector3::new(f32::NAN, 100.0, 100.0));
+        film.add_sample(0, 0, Vector3::new(100.0, 100.0, 100.0));
+        film.add_sample(1, 0, Vector3::repeat(f32::INFINITY));
+        film.add_splat(1, 0, Vector3::repeat(f32::NEG_INFINITY));
+        let image = film.to_image();
+        assert_eq!(image.pixel_at(0, 0), Vector3::new(50, 50, 50));
+        assert_eq!(image.pixel_at(1, 0), Vector3::new(0, 0, 0));
+    }
+}
@@ -0,0 +1,46 @@
+use nalgebra::Vector3;
+use std::f32::consts::PI;
+use crate::integrators::Integrator;
+use crate::ray::Ray;
+use crate::sampler::SampleStream;
+use crate::world::World;
+
+// Shades surfaces in gray by how open they are, sending one ray off the first surface hit and making the pixel
+// white if nothing is hit within distance and black otherwise. Rays that hit nothing are white.
+pub struct AmbientOcclusion {
+    distance: f32,
+}
+
+impl AmbientOcclusion {
+    pub fn new(distance: f32) -> Self {
+        if distance <= 0.0 {
+            panic!("Occlusion distance must be positive, given distance was {}", distance)
+        }
+        Self {
+            distance,
+        }
+    }
+}
+
+impl Integrator for AmbientOcclusion {
+    fn color(&self, world: &World, ray: &Ray, samples: &mut SampleStream, _max_bounces: u8) -> Vector3<f32> {
+        let hit = match world.hit(ray) {
+            Some(hit) => hit,
+            None => return Vector3::repeat(255.0),
+        };
+        let normal = if hit.normal().dot(&ray.dir()) > 0.0 { -hit.normal() } else { hit.normal() };
+        // Directions are spread by the cosine of their angle to the normal, like the light reaching the surface
+        let (u1, u2) = samples.next_2d();
+        let z = 1.0 - (2.0 * u1);
+        let r = (1.0 - (z * z)).max(0.0).sqrt();
+        let phi = 2.0 * PI * u2;
+        let dir = normal + Vector3::new(r * phi.cos(), r * phi.sin(), z);
+        if dir.magnitude() < 0.0001 {
+            return Vector3::repeat(255.0)
+        }
+        match world.hit(&Ray::with_time(hit.point, dir, ray.time())) {
+            Some(occluder) if occluder.t < self.distance => Vector3::zeros(),
+            _ => Vector3::repeat(255.0),
+        }
+    }
+}
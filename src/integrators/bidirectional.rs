@@ -0,0 +1,360 @@
+use nalgebra::{Point3, Vector3};
+use std::f32::consts::PI;
+use crate::camera::Camera;
+use crate::film::is_finite;
+use crate::integrators::{Integrator, Splat};
+use crate::materials::Material;
+use crate::ray::Ray;
+use crate::sampler::SampleStream;
+use crate::world::World;
+
+// Bidirectional path tracer. Every sample traces a subpath from the camera and another from a point on a light,
+// then joins every vertex of one to every vertex of the other. Paths found several ways are weighted by the
+// balance heuristic, so each is mostly counted through the way most likely to find it. Joining light subpaths
+// straight to the camera renders caustics seen on matte surfaces, like light focused through glass onto a table,
+// which tracing from the camera alone almost never finds. That only works with perspective cameras without
+// stereo and through render, render_with and the other film based renders; color on its own leaves it out.
+// Media dim the light along every path but nothing scatters in them, and the background is only seen by camera
+// subpaths.
+pub struct Bidirectional();
+
+impl Integrator for Bidirectional {
+    fn color(&self, world: &World, ray: &Ray, samples: &mut SampleStream, max_bounces: u8) -> Vector3<f32> {
+        trace(world, None, ray, samples, max_bounces).0
+    }
+
+    fn color_with_splats(&self, world: &World, camera: (&Point3<f32>, &Camera), ray: &Ray, samples: &mut SampleStream, max_bounces: u8) -> (Vector3<f32>, Vec<Splat>) {
+        trace(world, Some(camera), ray, samples, max_bounces)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Kind {
+    Camera,
+    Light,
+    Surface,
+}
+
+#[derive(Copy, Clone)]
+struct Vertex<'a> {
+    kind: Kind,
+    point: Point3<f32>,
+    normal: Vector3<f32>, // Outward surface normal, or the view direction of the camera
+    wo: Vector3<f32>, // Towards the previous vertex of the subpath
+    beta: Vector3<f32>, // Light or importance carried to the vertex over the density of sampling it, without a light's own emission
+    material: Option<&'a dyn Material>,
+    emitted: Vector3<f32>,
+    light_pdf: f32, // Density per unit of area of sample_light picking the vertex
+    delta: bool, // Whether the vertex only scatters along single directions
+    pdf_fwd: f32, // Density per unit of area of sampling the vertex from the previous one on its subpath
+    pdf_rev: f32, // Same from the next one, as if the path was traced the other way
+}
+
+impl<'a> Vertex<'a> {
+    fn new(kind: Kind, point: Point3<f32>, normal: Vector3<f32>, beta: Vector3<f32>) -> Self {
+        Self {
+            kind,
+            point,
+            normal,
+            wo: Vector3::zeros(),
+            beta,
+            material: None,
+            emitted: Vector3::zeros(),
+            light_pdf: 0.0,
+            delta: false,
+            pdf_fwd: 0.0,
+            pdf_rev: 0.0,
+        }
+    }
+
+    // Surface normal on the side dir points to
+    fn facing(&self, dir: &Vector3<f32>) -> Vector3<f32> {
+        if self.normal.dot(dir) < 0.0 { -self.normal } else { self.normal }
+    }
+
+    // Turns a density per unit of solid angle of sampling a direction from this vertex into one per unit of area at next
+    fn convert(&self, pdf_dir: f32, next: &Vertex) -> f32 {
+        let to_next = next.point - self.point;
+        let distance2 = to_next.magnitude_squared();
+        if distance2 == 0.0 {
+            return 0.0
+        }
+        let pdf = pdf_dir / distance2;
+        if next.kind == Kind::Camera {
+            return pdf
+        }
+        pdf * next.normal.dot(&to_next).abs() / distance2.sqrt()
+    }
+
+    // Fraction of light scattered from next towards the previous vertex
+    fn f(&self, next: &Vertex) -> Vector3<f32> {
+        let wi = (next.point - self.point).normalize();
+        match self.material {
+            Some(material) => material.eval(&self.wo, &wi, &self.facing(&self.wo)),
+            None => Vector3::zeros(),
+        }
+    }
+
+    // Light given off towards a point
+    fn emitted_towards(&self, point: &Point3<f32>) -> Vector3<f32> {
+        if self.normal.dot(&(point - self.point)) > 0.0 { self.emitted } else { Vector3::zeros() }
+    }
+
+    // Density per unit of area at next of the vertex sending a subpath there after arriving from prev
+    fn pdf(&self, camera: Option<(&Point3<f32>, &Camera)>, prev: Option<&Vertex>, next: &Vertex) -> f32 {
+        match self.kind {
+            Kind::Camera => self.convert(camera.map_or(0.0, |(_, camera)| camera.direction_pdf(&(next.point - self.point))), next),
+            Kind::Light => self.pdf_light(next),
+            Kind::Surface => {
+                let (material, prev) = match (self.material, prev) {
+                    (Some(material), Some(prev)) => (material, prev),
+                    _ => return 0.0,
+                };
+                let wo = (prev.point - self.point).normalize();
+                let wi = (next.point - self.point).normalize();
+                self.convert(material.pdf(&wo, &wi, &self.facing(&wo)), next)
+            },
+        }
+    }
+
+    // Density per unit of area at next of a light subpath leaving this vertex towards it
+    fn pdf_light(&self, next: &Vertex) -> f32 {
+        let wi = (next.point - self.point).normalize();
+        self.convert(self.normal.dot(&wi).max(0.0) / PI, next)
+    }
+}
+
+fn trace(world: &World, camera: Option<(&Point3<f32>, &Camera)>, ray: &Ray, samples: &mut SampleStream, max_bounces: u8) -> (Vector3<f32>, Vec<Splat>) {
+    let max_bounces = max_bounces as usize;
+    let light_tracing = camera.is_some_and(|(_, camera)| camera.direction_pdf(&camera.get_dir()) > 0.0);
+    let mut color: Vector3<f32> = Vector3::zeros();
+    let mut splats: Vec<Splat> = vec![];
+
+    let view = camera.map_or(ray.dir(), |(_, camera)| camera.get_dir());
+    let mut camera_path = vec![Vertex::new(Kind::Camera, ray.pos(), view, Vector3::repeat(1.0))];
+    let pdf_dir = camera.map_or(0.0, |(_, camera)| camera.direction_pdf(&ray.dir()));
+    if let Some(background) = walk(world, *ray, Vector3::repeat(1.0), pdf_dir, max_bounces + 2, samples, &mut camera_path) {
+        color += background;
+    }
+
+    let mut light_path: Vec<Vertex> = vec![];
+    if let Some(light) = world.sample_light(ray.time(), samples) {
+        let mut origin = Vertex::new(Kind::Light, light.point, light.normal, Vector3::repeat(1.0 / light.pdf));
+        origin.emitted = light.emitted;
+        origin.light_pdf = light.pdf;
+        origin.pdf_fwd = light.pdf;
+        light_path.push(origin);
+        // Light leaves by the cosine of its angle to the normal, which cancels out of beta but for pi
+        let dir = light.normal + uniform_sphere(samples.next_2d());
+        if dir.magnitude() > 0.0001 {
+            let dir = dir.normalize();
+            walk(world, Ray::with_time(light.point, dir, ray.time()), light.emitted * PI / light.pdf, light.normal.dot(&dir) / PI, max_bounces + 1, samples, &mut light_path);
+        }
+    }
+
+    for t in 1..=camera_path.len() {
+        for s in 0..=light_path.len() {
+            if (s == 1 && t == 1) || s + t < 2 || s + t - 2 > max_bounces {
+                continue;
+            }
+            if t == 1 {
+                if let (true, Some(camera)) = (light_tracing, camera) {
+                    if let Some((pixel, splat)) = connect_to_camera(world, camera, &light_path[..s], samples, ray.time()) {
+                        if is_finite(&splat) {
+                            splats.push((pixel, splat));
+                        }
+                    }
+                }
+            } else {
+                // A strategy that comes out NaN or infinite, like from a pdf of 0, is left out rather than spoiling the others
+                let contribution = connect(world, camera, light_tracing, &light_path[..s], &camera_path[..t], samples, ray.time());
+                if is_finite(&contribution) {
+                    color += contribution;
+                }
+            }
+        }
+    }
+    (color, splats)
+}
+
+// Extends a subpath with the vertices ray bounces through, up to max_vertices in all. Returns the light of the
+// background weighted by beta if the subpath leaves the scene.
+fn walk<'a>(world: &'a World, mut ray: Ray, mut beta: Vector3<f32>, mut pdf_dir: f32, max_vertices: usize, samples: &mut SampleStream, path: &mut Vec<Vertex<'a>>) -> Option<Vector3<f32>> {
+    while path.len() < max_vertices {
+        let hit = match world.hit(&ray) {
+            Some(hit) => hit,
+            None => return Some(beta.component_mul(&world.background(&ray)) * world.transmittance(&ray, f32::INFINITY, samples)),
+        };
+        beta *= world.transmittance(&ray, hit.t, samples);
+        let prev = path.len() - 1;
+        let mut vertex = Vertex::new(Kind::Surface, hit.point, hit.normal(), beta);
+        vertex.wo = -ray.dir();
+        vertex.material = Some(hit.material);
+        vertex.emitted = hit.material.emitted();
+        vertex.light_pdf = world.light_pdf(&hit);
+        vertex.delta = hit.material.is_specular();
+        vertex.pdf_fwd = path[prev].convert(pdf_dir, &vertex);
+        path.push(vertex);
+        if path.len() >= max_vertices {
+            break;
+        }
+
+        let bounce = hit.bounce(&ray, samples);
+        let (wo, wi) = (-ray.dir(), bounce.dir());
+        let pdf_rev_dir = if vertex.delta {
+            beta.component_mul_assign(&hit.material.attenuation());
+            pdf_dir = 0.0;
+            0.0
+        } else {
+            let normal = vertex.facing(&wo);
+            pdf_dir = hit.material.pdf(&wo, &wi, &normal);
+            if pdf_dir <= 0.0 {
+                break;
+            }
+            beta.component_mul_assign(&(hit.material.eval(&wo, &wi, &normal) * wi.dot(&normal).abs() / pdf_dir));
+            hit.material.pdf(&wi, &wo, &normal)
+        };
+        if beta.max() <= 0.0 {
+            break;
+        }
+        path[prev].pdf_rev = vertex.convert(pdf_rev_dir, &path[prev]);
+        ray = bounce;
+    }
+    None
+}
+
+// Light carried by the path made of the first s vertices of the light subpath followed by the last t >= 2
+// vertices of the camera subpath, weighted for the other ways of finding it. With s = 1 a new point is picked on a light.
+fn connect(world: &World, camera: Option<(&Point3<f32>, &Camera)>, light_tracing: bool, light_path: &[Vertex], camera_path: &[Vertex], samples: &mut SampleStream, time: f32) -> Vector3<f32> {
+    let (s, t) = (light_path.len(), camera_path.len());
+    let pt = camera_path[t - 1];
+    if s == 0 {
+        let light = pt.emitted_towards(&camera_path[t - 2].point);
+        if light.max() <= 0.0 {
+            return Vector3::zeros()
+        }
+        // Lights that can't be picked are only found by hitting them
+        let weight = if pt.light_pdf > 0.0 { mis_weight(camera, light_tracing, light_path, camera_path) } else { 1.0 };
+        return pt.beta.component_mul(&light) * weight
+    }
+    if pt.delta {
+        return Vector3::zeros()
+    }
+    let mut lights = light_path.to_vec();
+    if s == 1 {
+        let light = match world.sample_light(time, samples) {
+            Some(light) => light,
+            None => return Vector3::zeros(),
+        };
+        let mut sampled = Vertex::new(Kind::Light, light.point, light.normal, Vector3::repeat(1.0 / light.pdf));
+        sampled.emitted = light.emitted;
+        sampled.light_pdf = light.pdf;
+        sampled.pdf_fwd = light.pdf;
+        lights[0] = sampled;
+    }
+    let qs = lights[s - 1];
+    if qs.delta {
+        return Vector3::zeros()
+    }
+    let scattered = if qs.kind == Kind::Light { qs.emitted_towards(&pt.point) } else { qs.f(&pt) };
+    let contribution = qs.beta.component_mul(&scattered).component_mul(&pt.f(&qs)).component_mul(&pt.beta) * geometry(&qs, &pt);
+    if contribution.max() <= 0.0 {
+        return Vector3::zeros()
+    }
+    contribution * (world.transmittance_between(&pt.point, &qs.point, time, samples) * mis_weight(camera, light_tracing, &lights, camera_path))
+}
+
+// Joins the end of a light subpath of s >= 2 vertices to a point on the camera lens, returning the pixel it
+// lands on and the light it leaves there
+fn connect_to_camera(world: &World, camera: (&Point3<f32>, &Camera), light_path: &[Vertex], samples: &mut SampleStream, time: f32) -> Option<Splat> {
+    let qs = light_path[light_path.len() - 1];
+    let lens = samples.next_2d();
+    if qs.delta {
+        return None
+    }
+    let (pos, cam) = camera;
+    let (lens_point, (px, py), importance) = cam.connect(pos, &qs.point, lens)?;
+    let mut eye = Vertex::new(Kind::Camera, lens_point, cam.get_dir(), Vector3::repeat(importance));
+    eye.pdf_fwd = 1.0;
+    let to_eye = (lens_point - qs.point).normalize();
+    let cos_eye = -to_eye.dot(&cam.get_dir());
+    let contribution = qs.beta.component_mul(&qs.f(&eye)) * (importance * qs.normal.dot(&to_eye).abs() * cos_eye / (lens_point - qs.point).magnitude_squared());
+    if contribution.max() <= 0.0 {
+        return None
+    }
+    let transmittance = world.transmittance_between(&qs.point, &lens_point, time, samples);
+    if transmittance <= 0.0 {
+        return None
+    }
+    Some(((px as usize, py as usize), contribution * (transmittance * mis_weight(Some(camera), true, light_path, &[eye]))))
+}
+
+// Geometric term between two vertices joined by a straight line
+fn geometry(a: &Vertex, b: &Vertex) -> f32 {
+    let to_b = b.point - a.point;
+    let distance2 = to_b.magnitude_squared();
+    let dir = to_b / distance2.sqrt();
+    a.normal.dot(&dir).abs() * b.normal.dot(&dir).abs() / distance2
+}
+
+// Balance heuristic weight of the path joining the light and camera subpaths, found by comparing the densities
+// of every other way to split it into a light and a camera subpath with this one
+fn mis_weight(camera: Option<(&Point3<f32>, &Camera)>, light_tracing: bool, light_path: &[Vertex], camera_path: &[Vertex]) -> f32 {
+    let (s, t) = (light_path.len(), camera_path.len());
+    if s + t == 2 {
+        return 1.0
+    }
+    let mut lights = light_path.to_vec();
+    let mut cameras = camera_path.to_vec();
+
+    // The joined vertices and their neighbours could also have been sampled from the other side of the join
+    let pt = cameras[t - 1];
+    let qs = if s > 0 { Some(lights[s - 1]) } else { None };
+    let pt_minus = if t > 1 { Some(cameras[t - 2]) } else { None };
+    let qs_minus = if s > 1 { Some(lights[s - 2]) } else { None };
+    cameras[t - 1].pdf_rev = match &qs {
+        Some(qs) => qs.pdf(camera, qs_minus.as_ref(), &pt),
+        None => pt.light_pdf,
+    };
+    cameras[t - 1].delta = false;
+    if let Some(pt_minus) = &pt_minus {
+        cameras[t - 2].pdf_rev = match &qs {
+            Some(qs) => pt.pdf(camera, Some(qs), pt_minus),
+            None => pt.pdf_light(pt_minus),
+        };
+    }
+    if let Some(qs) = &qs {
+        lights[s - 1].pdf_rev = pt.pdf(camera, pt_minus.as_ref(), qs);
+        lights[s - 1].delta = false;
+        if let Some(qs_minus) = &qs_minus {
+            lights[s - 2].pdf_rev = qs.pdf(camera, Some(&pt), qs_minus);
+        }
+    }
+
+    let remap = |pdf: f32| if pdf != 0.0 { pdf } else { 1.0 };
+    let mut sum = 0.0;
+    let mut ratio = 1.0;
+    for i in (1..t).rev() {
+        ratio *= remap(cameras[i].pdf_rev) / remap(cameras[i].pdf_fwd);
+        // Splitting before the second camera vertex means joining to the camera
+        if !cameras[i].delta && !cameras[i - 1].delta && (i > 1 || light_tracing) {
+            sum += ratio;
+        }
+    }
+    ratio = 1.0;
+    for i in (0..s).rev() {
+        ratio *= remap(lights[i].pdf_rev) / remap(lights[i].pdf_fwd);
+        if !lights[i].delta && (i == 0 || !lights[i - 1].delta) {
+            sum += ratio;
+        }
+    }
+    1.0 / (1.0 + sum)
+}
+
+// Uniformly distributed direction from a point in [0, 1]^2
+fn uniform_sphere((u1, u2): (f32, f32)) -> Vector3<f32> {
+    let z = 1.0 - (2.0 * u1);
+    let r = (1.0 - (z * z)).max(0.0).sqrt();
+    let phi = 2.0 * PI * u2;
+    Vector3::new(r * phi.cos(), r * phi.sin(), z)
+}
@@ -0,0 +1,26 @@
+use nalgebra::Vector3;
+use crate::integrators::Integrator;
+use crate::ray::Ray;
+use crate::sampler::SampleStream;
+use crate::world::World;
+
+// Shows the geometry of the first surface each ray hits, rays that hit nothing are black
+pub enum Debug {
+    // Outward surface normals, with each axis mapped from -1 to 1 onto 0 to 255 of a channel
+    Normals,
+    // Distance from the ray origin, white up close fading to black at the given distance
+    Depth(f32),
+}
+
+impl Integrator for Debug {
+    fn color(&self, world: &World, ray: &Ray, _samples: &mut SampleStream, _max_bounces: u8) -> Vector3<f32> {
+        let hit = match world.hit(ray) {
+            Some(hit) => hit,
+            None => return Vector3::zeros(),
+        };
+        match self {
+            Debug::Normals => (hit.normal() + Vector3::repeat(1.0)) * 127.5,
+            Debug::Depth(max_depth) => Vector3::repeat(255.0 * (1.0 - (hit.t / max_depth)).max(0.0)),
+        }
+    }
+}
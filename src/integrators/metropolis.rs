@@ -0,0 +1,246 @@
+use nalgebra::{Point3, Vector3};
+use std::cell::RefCell;
+use std::f32::consts::PI;
+use crate::camera::Camera;
+use crate::film::is_finite;
+use crate::integrators::Integrator;
+use crate::integrators::path::PathTracer;
+use crate::ppmhandler::PPMImage;
+use crate::sampler::{Sampler, hash, to_unit};
+use crate::world::World;
+
+// Primary sample space Metropolis light transport, from Kelemen et al.'s "A Simple and Robust Mutation Strategy
+// for the Metropolis Light Transport Algorithm". Paths are traced by the path tracer, but instead of drawing fresh
+// random numbers for every path, chains of paths are made by nudging the numbers of the last path a little and
+// keeping the change with a chance that follows how bright the new path is. Once a chain finds a hard to reach
+// bright path, like light coming through a keyhole, it keeps exploring the paths around it.
+//
+// bootstrap_samples independent paths estimate the overall brightness of the image and pick where the chains
+// start. Each step is a large step drawing all new numbers with chance large_step_probability, and otherwise a
+// small step moving each number by about small_step_size. Small steps around 0.01 explore well, larger ones
+// move around more but get turned down more often.
+pub struct Metropolis {
+    bootstrap_samples: u32,
+    chains: u32,
+    mutations_per_pixel: u32,
+    large_step_probability: f32,
+    small_step_size: f32,
+}
+
+impl Metropolis {
+    pub fn new(bootstrap_samples: u32, chains: u32, mutations_per_pixel: u32, large_step_probability: f32, small_step_size: f32) -> Self {
+        if bootstrap_samples == 0 || chains == 0 {
+            panic!("Metropolis rendering needs at least 1 bootstrap sample and 1 chain, given {} and {}", bootstrap_samples, chains)
+        }
+        if mutations_per_pixel == 0 {
+            panic!("Metropolis rendering needs at least 1 mutation per pixel")
+        }
+        if !(0.0..=1.0).contains(&large_step_probability) {
+            panic!("Large step probability must be a number between 0 and 1, given probability was {}", large_step_probability)
+        }
+        if small_step_size <= 0.0 {
+            panic!("Small step size must be positive, given size was {}", small_step_size)
+        }
+        Self {
+            bootstrap_samples,
+            chains,
+            mutations_per_pixel,
+            large_step_probability,
+            small_step_size,
+        }
+    }
+
+    pub fn get_large_step_probability(&self) -> f32 {
+        self.large_step_probability
+    }
+
+    pub fn get_small_step_size(&self) -> f32 {
+        self.small_step_size
+    }
+
+    // Renders the camera at pos, with the same seed giving the same image
+    pub fn render(&self, world: &World, (pos, camera): (&Point3<f32>, &Camera), max_bounces: u8, seed: u32) -> PPMImage {
+        let (width, height) = (camera.get_iwidth(), camera.get_iheight());
+        // Paths are left to run to max_bounces, as ending them at random makes brightness jump between nearby paths
+        let tracer = PathTracer::new(u8::MAX);
+        let trace = |primary: &PrimarySamples| primary.trace(world, &tracer, (pos, camera), max_bounces, seed);
+
+        let weights: Vec<f32> = (0..self.bootstrap_samples)
+            .map(|index| trace(&PrimarySamples::new(hash(&[seed, index]), 0, self.small_step_size, (width, height))).2)
+            .collect();
+        let total: f32 = weights.iter().sum();
+        let mut image = PPMImage::new(width, height, 255);
+        if total <= 0.0 {
+            return image
+        }
+        let brightness = total / self.bootstrap_samples as f32;
+
+        let mut splats = vec![vec![Vector3::<f32>::zeros(); height]; width];
+        let mutations = self.mutations_per_pixel as u64 * (width * height) as u64;
+        let mutations_per_chain = mutations.div_ceil(self.chains as u64);
+        for chain in 0..self.chains {
+            // Chains start from bootstrap paths picked in proportion to their brightness
+            let mut pick = to_unit(hash(&[seed, chain, 0x5eed])) * total;
+            let start = weights.iter().position(|weight| { pick -= weight; pick < 0.0 }).unwrap_or(weights.len() - 1);
+            let primary = PrimarySamples::new(hash(&[seed, start as u32]), hash(&[seed, chain, 0xc4a1]), self.small_step_size, (width, height));
+            let mut current = trace(&primary);
+            for step in 0..mutations_per_chain {
+                let step = step as u32;
+                primary.start_step(to_unit(hash(&[seed, chain, step, 0x1a26])) < self.large_step_probability);
+                let proposed = trace(&primary);
+                let accept = if current.2 > 0.0 { (proposed.2 / current.2).min(1.0) } else { 1.0 };
+                // Both paths are counted by how likely each is to be kept, which wastes fewer paths than counting only the kept one
+                if let ((x, y), Some(color), weight) = proposed {
+                    if weight > 0.0 && accept > 0.0 {
+                        splats[x][y] += color * (accept / weight);
+                    }
+                }
+                if let ((x, y), Some(color), weight) = current {
+                    if weight > 0.0 && accept < 1.0 {
+                        splats[x][y] += color * ((1.0 - accept) / weight);
+                    }
+                }
+                if to_unit(hash(&[seed, chain, step, 0xacce])) < accept {
+                    current = proposed;
+                    primary.accept();
+                } else {
+                    primary.reject();
+                }
+            }
+        }
+
+        let scale = brightness * (width * height) as f32 / (mutations_per_chain * self.chains as u64) as f32;
+        for (x, column) in splats.iter().enumerate() {
+            for (y, splat) in column.iter().enumerate() {
+                image.change_pixel(x, y, (splat * scale).map(|c| if c.is_nan() { 0.0 } else { c.clamp(0.0, 255.0) }).try_cast::<u32>().unwrap());
+            }
+        }
+        image
+    }
+}
+
+// One number of the primary sample vector with what's needed to bring it up to date and undo its last change
+#[derive(Copy, Clone)]
+struct PrimarySample {
+    value: f32,
+    backup: f32,
+    modified: u32, // Step the value was last brought up to
+    backup_modified: u32,
+}
+
+// Where a chain is in its steps
+#[derive(Copy, Clone, Default)]
+struct Steps {
+    current: u32,
+    last_large: u32, // Last kept large step
+    large: bool, // Whether the current step is a large one
+    draws: u32, // Steps tried so far, kept or not, so turned down steps aren't tried again
+}
+
+// The vector of random numbers a path is traced from, which starts out drawn from seed and is then moved by
+// steps. Numbers are only brought up to date when a path asks for them, so long paths cost nothing until used.
+struct PrimarySamples {
+    samples: RefCell<Vec<PrimarySample>>,
+    seed: u32, // Draws the starting numbers
+    chain_seed: u32, // Draws the steps
+    small_step_size: f32,
+    dim: (usize, usize), // Image size, the first two numbers pick a position on the image
+    steps: RefCell<Steps>,
+}
+
+impl PrimarySamples {
+    fn new(seed: u32, chain_seed: u32, small_step_size: f32, dim: (usize, usize)) -> Self {
+        Self {
+            samples: RefCell::new(vec![]),
+            seed,
+            chain_seed,
+            small_step_size,
+            dim,
+            steps: RefCell::new(Steps::default()),
+        }
+    }
+
+    fn start_step(&self, large: bool) {
+        let mut steps = self.steps.borrow_mut();
+        steps.current += 1;
+        steps.draws += 1;
+        steps.large = large;
+    }
+
+    // Keeps the numbers of the current step
+    fn accept(&self) {
+        let mut steps = self.steps.borrow_mut();
+        if steps.large {
+            steps.last_large = steps.current;
+        }
+    }
+
+    // Goes back to the numbers from before the current step
+    fn reject(&self) {
+        let mut steps = self.steps.borrow_mut();
+        for sample in self.samples.borrow_mut().iter_mut() {
+            if sample.modified == steps.current {
+                sample.value = sample.backup;
+                sample.modified = sample.backup_modified;
+            }
+        }
+        steps.current -= 1;
+    }
+
+    fn get(&self, dim: u32) -> f32 {
+        let steps = *self.steps.borrow();
+        let mut samples = self.samples.borrow_mut();
+        while samples.len() <= dim as usize {
+            let first = to_unit(hash(&[self.seed, samples.len() as u32]));
+            samples.push(PrimarySample { value: first, backup: first, modified: 0, backup_modified: 0 });
+        }
+        let sample = &mut samples[dim as usize];
+        if sample.modified < steps.current {
+            // Numbers not used since a kept large step would have been drawn anew by it
+            if sample.modified < steps.last_large {
+                sample.value = to_unit(hash(&[self.chain_seed, steps.draws, dim, 0]));
+                sample.modified = steps.last_large;
+            }
+            sample.backup = sample.value;
+            sample.backup_modified = sample.modified;
+            if steps.large {
+                sample.value = to_unit(hash(&[self.chain_seed, steps.draws, dim, 1]));
+            } else {
+                // Small steps add up like a normal distribution, so all the ones missed are taken in one go
+                let missed = (steps.current - sample.modified) as f32;
+                let (u1, u2) = (to_unit(hash(&[self.chain_seed, steps.draws, dim, 2])), to_unit(hash(&[self.chain_seed, steps.draws, dim, 3])));
+                let normal = (-2.0 * (1.0 - u1).ln()).sqrt() * (2.0 * PI * u2).cos();
+                sample.value = (sample.value + normal * self.small_step_size * missed.sqrt()).rem_euclid(1.0);
+                if sample.value >= 1.0 {
+                    sample.value = 0.0;
+                }
+            }
+            sample.modified = steps.current;
+        }
+        sample.value
+    }
+
+    // Traces the path of the current numbers, returning its pixel, its color if the camera covers that position and its brightness
+    fn trace(&self, world: &World, tracer: &PathTracer, (pos, camera): (&Point3<f32>, &Camera), max_bounces: u8, seed: u32) -> ((usize, usize), Option<Vector3<f32>>, f32) {
+        let (width, height) = self.dim;
+        let x = ((self.get(0) * width as f32) as usize).min(width - 1);
+        let y = ((self.get(1) * height as f32) as usize).min(height - 1);
+        // Paths that come out NaN or infinite are left dark, as they would otherwise take over the chain's brightness
+        let color = camera.get_ray(pos, (x, y), 0, 1, self, seed)
+            .map(|(ray, mut samples)| tracer.color(world, &ray, &mut samples, max_bounces))
+            .map(|color| if is_finite(&color) { color.map(|c| c.max(0.0)) } else { Vector3::zeros() });
+        let weight = color.map_or(0.0, |color| (color.x + color.y + color.z) / 3.0);
+        ((x, y), color, weight)
+    }
+}
+
+// The first two numbers pick the pixel, and give the position within it here
+impl Sampler for PrimarySamples {
+    fn sample(&self, _pixel: (usize, usize), _index: u32, _count: u32, dim: u32, _seed: u32) -> f32 {
+        match dim {
+            0 => (self.get(0) * self.dim.0 as f32).fract(),
+            1 => (self.get(1) * self.dim.1 as f32).fract(),
+            _ => self.get(dim),
+        }
+    }
+}
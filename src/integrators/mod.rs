@@ -0,0 +1,32 @@
+use nalgebra::{Point3, Vector3};
+use crate::camera::Camera;
+use crate::ray::Ray;
+use crate::sampler::SampleStream;
+use crate::world::World;
+
+pub mod recursive;
+pub mod path;
+pub mod whitted;
+pub mod ambient_occlusion;
+pub mod debug;
+pub mod bidirectional;
+pub mod photon;
+pub mod metropolis;
+pub mod spectral;
+
+// Light landing on the pixel at (x, y), see Integrator::color_with_splats
+pub type Splat = ((usize, usize), Vector3<f32>);
+
+// Rendering algorithm working out the color seen along camera rays
+pub trait Integrator {
+    // Color seen along a ray, on the same 0 to 255 scale as colors but unclamped so that bright samples keep
+    // their weight in pixel averages. max_bounces caps how many times the ray is followed off surfaces and media.
+    fn color(&self, world: &World, ray: &Ray, samples: &mut SampleStream, max_bounces: u8) -> Vector3<f32>;
+
+    // Light Tracing Requirement
+    // Like color, but integrators carrying light from lights to the camera at pos may also return the light
+    // landing on any pixel as ((x, y), color) splats, which renders average over all samples of the image.
+    fn color_with_splats(&self, world: &World, _camera: (&Point3<f32>, &Camera), ray: &Ray, samples: &mut SampleStream, max_bounces: u8) -> (Vector3<f32>, Vec<Splat>) {
+        (self.color(world, ray, samples, max_bounces), vec![])
+    }
+}
@@ -0,0 +1,60 @@
+use nalgebra::Vector3;
+use crate::integrators::Integrator;
+use crate::ray::Ray;
+use crate::sampler::SampleStream;
+use crate::world::World;
+
+const MIN_SURVIVAL: f32 = 0.05; // Lowest chance a path has of surviving russian roulette
+
+// Follows each ray as a path through the scene, carrying how much light survives every bounce. Surfaces tint
+// the light they bounce by their attenuation instead of blending by reflectance. After roulette_bounces
+// bounces paths are ended at random, more likely the less light they still carry, and the surviving ones
+// are weighted up to keep the average right.
+pub struct PathTracer {
+    roulette_bounces: u8,
+}
+
+impl PathTracer {
+    pub fn new(roulette_bounces: u8) -> Self {
+        Self {
+            roulette_bounces,
+        }
+    }
+}
+
+impl Integrator for PathTracer {
+    fn color(&self, world: &World, ray: &Ray, samples: &mut SampleStream, max_bounces: u8) -> Vector3<f32> {
+        let mut color: Vector3<f32> = Vector3::zeros();
+        let mut throughput: Vector3<f32> = Vector3::repeat(1.0);
+        let mut ray = *ray;
+        for num_bounces in 0..max_bounces {
+            let hit = world.hit(&ray);
+            let t_surface = hit.as_ref().map_or(f32::INFINITY, |hit| hit.t);
+            if let Some((event, phase)) = world.medium_event(&ray, t_surface, samples) {
+                color += throughput.component_mul(&event.emission);
+                throughput.component_mul_assign(&event.albedo);
+                ray = Ray::with_wavelength(ray.at(event.t), phase.sample(&ray.dir(), samples), ray.time(), ray.wavelength());
+            } else {
+                match hit {
+                    Some(hit) => {
+                        color += throughput.component_mul(&hit.material.emitted());
+                        throughput.component_mul_assign(&hit.material.attenuation());
+                        ray = hit.bounce(&ray, samples);
+                    },
+                    None => return color + throughput.component_mul(&world.background(&ray)),
+                }
+            }
+            if throughput.max() <= 0.0 {
+                break;
+            }
+            if num_bounces + 1 >= self.roulette_bounces {
+                let survival = throughput.max().clamp(MIN_SURVIVAL, 1.0);
+                if samples.next_1d() >= survival {
+                    break;
+                }
+                throughput /= survival;
+            }
+        }
+        color
+    }
+}
@@ -0,0 +1,111 @@
+use nalgebra::Point3;
+
+// Balanced kd-tree of items at points, for finding every item near a point. The tree is stored implicitly:
+// each range of items has its splitting item in the middle, with the items before it on the low side of
+// its splitting axis and the items after it on the high side.
+pub struct KdTree<T> {
+    items: Vec<(Point3<f32>, T)>,
+    axes: Vec<usize>, // Splitting axis of the item at the same index
+}
+
+impl<T> KdTree<T> {
+    pub fn new(mut items: Vec<(Point3<f32>, T)>) -> Self {
+        let mut axes = vec![0; items.len()];
+        build(&mut items, &mut axes);
+        Self {
+            items,
+            axes,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    // Calls visit with every item within radius of point
+    pub fn within<F: FnMut(&Point3<f32>, &T)>(&self, point: &Point3<f32>, radius: f32, mut visit: F) {
+        self.search(0, self.items.len(), point, radius.powi(2), &mut visit);
+    }
+
+    fn search<F: FnMut(&Point3<f32>, &T)>(&self, start: usize, end: usize, point: &Point3<f32>, radius2: f32, visit: &mut F) {
+        if start >= end {
+            return;
+        }
+        let mid = start + ((end - start) / 2);
+        let (item_point, item) = &self.items[mid];
+        if (item_point - point).magnitude_squared() <= radius2 {
+            visit(item_point, item);
+        }
+        let axis = self.axes[mid];
+        let offset = point[axis] - item_point[axis];
+        let (near, far) = if offset < 0.0 { ((start, mid), (mid + 1, end)) } else { ((mid + 1, end), (start, mid)) };
+        self.search(near.0, near.1, point, radius2, visit);
+        if offset.powi(2) <= radius2 {
+            self.search(far.0, far.1, point, radius2, visit);
+        }
+    }
+}
+
+// Splits items along the axis they spread out furthest on around their median, then each side the same way
+fn build<T>(items: &mut [(Point3<f32>, T)], axes: &mut [usize]) {
+    if items.len() <= 1 {
+        return;
+    }
+    let (mut min, mut max) = (items[0].0, items[0].0);
+    for (point, _) in items.iter() {
+        min = min.inf(point);
+        max = max.sup(point);
+    }
+    let extent = max - min;
+    let axis = extent.iamax();
+    let mid = items.len() / 2;
+    items.select_nth_unstable_by(mid, |a, b| a.0[axis].total_cmp(&b.0[axis]));
+    axes[mid] = axis;
+    let (low_items, rest) = items.split_at_mut(mid);
+    let (low_axes, rest_axes) = axes.split_at_mut(mid);
+    build(low_items, low_axes);
+    build(&mut rest[1..], &mut rest_axes[1..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sampler::{hash, to_unit};
+
+    fn point(i: u32) -> Point3<f32> {
+        Point3::new(to_unit(hash(&[i, 0])), to_unit(hash(&[i, 1])), to_unit(hash(&[i, 2])) * 0.1)
+    }
+
+    #[test]
+    fn finds_the_same_items_as_checking_every_one() {
+        // Some points repeat so items also sit right on splitting planes
+        let points: Vec<Point3<f32>> = (0..500).map(|i| point(i % 400)).collect();
+        let tree = KdTree::new(points.iter().cloned().enumerate().map(|(i, point)| (point, i)).collect());
+        assert_eq!(tree.len(), points.len());
+        for query in 0..50 {
+            let center = point(1000 + query);
+            for radius in [0.0, 0.05, 0.2, 2.0] {
+                let mut found: Vec<usize> = vec![];
+                tree.within(&center, radius, |_, i| found.push(*i));
+                found.sort();
+                let expected: Vec<usize> = (0..points.len()).filter(|i| (points[*i] - center).magnitude_squared() <= radius.powi(2)).collect();
+                assert_eq!(found, expected);
+            }
+            // Searching from an item's own point always finds it
+            let mut found = vec![];
+            tree.within(&points[query as usize], 0.0, |_, i| found.push(*i));
+            assert!(found.contains(&(query as usize)));
+        }
+    }
+
+    #[test]
+    fn empty_tree_finds_nothing() {
+        let tree: KdTree<u32> = KdTree::new(vec![]);
+        assert!(tree.is_empty());
+        tree.within(&Point3::origin(), 1.0, |_, _| panic!("Empty tree visited an item"));
+    }
+}
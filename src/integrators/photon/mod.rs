@@ -0,0 +1,137 @@
+use nalgebra::{Point3, Vector3};
+use std::f32::consts::PI;
+use crate::integrators::Integrator;
+use crate::integrators::whitted::direct_light;
+use crate::ray::Ray;
+use crate::sampler::{SampleStream, hash};
+use crate::sampler::sobol::Sobol;
+use crate::world::World;
+use kdtree::KdTree;
+
+pub mod kdtree;
+
+const MIN_SURVIVAL: f32 = 0.05; // Lowest chance a photon has of surviving russian roulette
+
+// Light arriving at a matte surface, carried by a photon
+#[derive(Copy, Clone)]
+struct Photon {
+    wi: Vector3<f32>, // Towards where the photon came from
+    power: Vector3<f32>, // On the same scale as colors
+}
+
+// Two pass photon mapper. Building it sends photons out from the world's lights and stores them in a kd-tree
+// wherever they land on a matte surface after bouncing at least once. Rendering then follows camera rays through
+// specular surfaces to the first matte one, which is lit by the light reaching it straight from the lights plus
+// the photons within radius of it. Caustics show up as clusters of photons focused by glass or mirrors. The map
+// is blurred by the radius and is only right for the world it was built from. Photons are traced with objects
+// where they are at time 0 and the background doesn't light anything. Media dim photons and camera rays alike,
+// but nothing scatters in them.
+pub struct PhotonMapper {
+    photons: KdTree<Photon>,
+    radius: f32,
+}
+
+impl PhotonMapper {
+    pub fn new(world: &World, photon_count: u32, radius: f32, max_bounces: u8) -> Self {
+        Self::with_seed(world, photon_count, radius, max_bounces, world.get_seed())
+    }
+
+    // Photons come out the same for the same seed
+    pub fn with_seed(world: &World, photon_count: u32, radius: f32, max_bounces: u8, seed: u32) -> Self {
+        if radius <= 0.0 {
+            panic!("Photon radius must be positive, given radius was {}", radius)
+        }
+        let sampler = Sobol();
+        let mut photons: Vec<(Point3<f32>, Photon)> = vec![];
+        for index in 0..photon_count {
+            let mut samples = SampleStream::new(&sampler, (0, 0), index, photon_count, hash(&[seed, photon_count]));
+            trace_photon(world, &mut samples, photon_count, max_bounces, &mut photons);
+        }
+        Self {
+            photons: KdTree::new(photons),
+            radius,
+        }
+    }
+
+    pub fn get_radius(&self) -> f32 {
+        self.radius
+    }
+
+    // Number of photons stored in the map, which may be more or fewer than were sent out
+    pub fn photon_count(&self) -> usize {
+        self.photons.len()
+    }
+}
+
+impl Integrator for PhotonMapper {
+    fn color(&self, world: &World, ray: &Ray, samples: &mut SampleStream, max_bounces: u8) -> Vector3<f32> {
+        let mut color: Vector3<f32> = Vector3::zeros();
+        let mut throughput: Vector3<f32> = Vector3::repeat(1.0);
+        let mut ray = *ray;
+        for _ in 0..max_bounces {
+            let hit = match world.hit(&ray) {
+                Some(hit) => hit,
+                None => return color + throughput.component_mul(&world.background(&ray)) * world.transmittance(&ray, f32::INFINITY, samples),
+            };
+            throughput *= world.transmittance(&ray, hit.t, samples);
+            let wo = -ray.dir();
+            if hit.normal().dot(&wo) > 0.0 {
+                color += throughput.component_mul(&hit.material.emitted());
+            }
+            if hit.material.is_specular() {
+                throughput.component_mul_assign(&hit.material.attenuation());
+                ray = hit.bounce(&ray, samples);
+                continue;
+            }
+            let normal = if hit.normal().dot(&wo) < 0.0 { -hit.normal() } else { hit.normal() };
+            let mut gathered: Vector3<f32> = Vector3::zeros();
+            self.photons.within(&hit.point, self.radius, |_, photon| {
+                gathered += hit.material.eval(&wo, &photon.wi, &normal).component_mul(&photon.power);
+            });
+            let indirect = gathered / (PI * self.radius.powi(2));
+            return color + throughput.component_mul(&(direct_light(world, &ray, &hit, samples) + indirect))
+        }
+        color
+    }
+}
+
+// Sends one photon out from a point picked on a light, storing it at every matte surface it lands on after its first bounce
+fn trace_photon(world: &World, samples: &mut SampleStream, photon_count: u32, max_bounces: u8, photons: &mut Vec<(Point3<f32>, Photon)>) {
+    let light = match world.sample_light(0.0, samples) {
+        Some(light) => light,
+        None => return,
+    };
+    // Photons leave by the cosine of their angle to the normal, which cancels out of their power but for pi
+    let (u1, u2) = samples.next_2d();
+    let z = 1.0 - (2.0 * u1);
+    let r = (1.0 - (z * z)).max(0.0).sqrt();
+    let phi = 2.0 * PI * u2;
+    let dir = light.normal + Vector3::new(r * phi.cos(), r * phi.sin(), z);
+    if dir.magnitude() < 0.0001 {
+        return;
+    }
+    let power = light.emitted * PI / (light.pdf * photon_count as f32);
+    let mut throughput: Vector3<f32> = Vector3::repeat(1.0);
+    let mut ray = Ray::new(light.point, dir);
+    for num_bounces in 0..max_bounces {
+        let hit = match world.hit(&ray) {
+            Some(hit) => hit,
+            None => return,
+        };
+        throughput *= world.transmittance(&ray, hit.t, samples);
+        let wi = -ray.dir();
+        let normal = if hit.normal().dot(&wi) < 0.0 { -hit.normal() } else { hit.normal() };
+        // Light reaching a surface straight from a light is worked out exactly when rendering
+        let matte = !hit.material.is_specular() && hit.material.eval(&wi, &wi, &normal).max() > 0.0;
+        if matte && num_bounces > 0 {
+            photons.push((hit.point, Photon { wi, power: power.component_mul(&throughput) }));
+        }
+        throughput.component_mul_assign(&hit.material.attenuation());
+        let survival = throughput.max().clamp(MIN_SURVIVAL, 1.0);
+        if throughput.max() <= 0.0 || samples.next_1d() >= survival {
+            return;
+        }
+        throughput /= survival;
+        ray = hit.bounce(&ray, samples);
+    }
+}
@@ -0,0 +1,37 @@
+use nalgebra::Vector3;
+use crate::integrators::Integrator;
+use crate::ray::Ray;
+use crate::sampler::SampleStream;
+use crate::world::World;
+
+// The original tracer. Blends each surface's color with what its bounced ray sees by the surface's reflectance,
+// and shows the background once max_bounces is reached. Colors are rounded down to whole values at every bounce.
+pub struct Recursive();
+
+impl Integrator for Recursive {
+    fn color(&self, world: &World, ray: &Ray, samples: &mut SampleStream, max_bounces: u8) -> Vector3<f32> {
+        ray_color(world, ray, samples, 0, max_bounces).cast::<f32>()
+    }
+}
+
+fn ray_color(world: &World, ray: &Ray, samples: &mut SampleStream, num_bounces: u8, max_bounces: u8) -> Vector3<u8> {
+    if num_bounces >= max_bounces {
+        return world.background(ray).try_cast::<u8>().unwrap()
+    }
+    let hit = world.hit(ray);
+    let t_surface = hit.as_ref().map_or(f32::INFINITY, |hit| hit.t);
+    if let Some((event, phase)) = world.medium_event(ray, t_surface, samples) {
+        let scattered = Ray::with_time(ray.at(event.t), phase.sample(&ray.dir(), samples), ray.time());
+        return (event.emission + event.albedo.component_mul(&ray_color(world, &scattered, samples, num_bounces + 1, max_bounces).cast::<f32>()))
+            .map(|c| c.min(255.0)).try_cast::<u8>().unwrap()
+    }
+    match hit {
+        Some(hit) => {
+            let bounce = hit.bounce(ray, samples);
+            (((1.0 - hit.material.reflectance()) * hit.material.color().cast::<f32>()) +
+            (ray_color(world, &bounce, samples, num_bounces + 1, max_bounces).cast::<f32>() * hit.material.reflectance()))
+            .try_cast::<u8>().unwrap()
+        },
+        None => world.background(ray).try_cast::<u8>().unwrap(),
+    }
+}
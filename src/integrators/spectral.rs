@@ -0,0 +1,71 @@
+use nalgebra::{Vector3, Vector4};
+use crate::integrators::Integrator;
+use crate::ray::Ray;
+use crate::sampler::SampleStream;
+use crate::spectrum::{RgbSpectrum, Wavelengths};
+use crate::world::World;
+
+const MIN_SURVIVAL: f32 = 0.05; // Lowest chance a path has of surviving russian roulette
+
+// Path tracing with light carried at a handful of wavelengths instead of as RGB color, see Wavelengths. Colors
+// of materials, media and the background are turned into smooth spectra, so scenes look the same as with the
+// path tracer except where a surface bends each wavelength differently, like a dispersive dielectric splitting
+// white light into a rainbow. Each path picks its wavelengths with one more sample before its bounces.
+pub struct SpectralPathTracer {
+    roulette_bounces: u8,
+}
+
+impl SpectralPathTracer {
+    pub fn new(roulette_bounces: u8) -> Self {
+        Self {
+            roulette_bounces,
+        }
+    }
+}
+
+impl Integrator for SpectralPathTracer {
+    fn color(&self, world: &World, ray: &Ray, samples: &mut SampleStream, max_bounces: u8) -> Vector3<f32> {
+        let mut wavelengths = Wavelengths::sample(samples.next_1d());
+        let mut radiance: Vector4<f32> = Vector4::zeros();
+        let mut throughput: Vector4<f32> = Vector4::repeat(1.0);
+        let mut ray = Ray::with_wavelength(ray.pos(), ray.dir(), ray.time(), Some(wavelengths.hero()));
+        let spectrum = |rgb: &Vector3<f32>, wavelengths: &Wavelengths| RgbSpectrum::new(rgb).sample(wavelengths);
+        for num_bounces in 0..max_bounces {
+            let hit = world.hit(&ray);
+            let t_surface = hit.as_ref().map_or(f32::INFINITY, |hit| hit.t);
+            if let Some((event, phase)) = world.medium_event(&ray, t_surface, samples) {
+                radiance += throughput.component_mul(&spectrum(&event.emission, &wavelengths));
+                throughput.component_mul_assign(&spectrum(&event.albedo, &wavelengths));
+                ray = Ray::with_wavelength(ray.at(event.t), phase.sample(&ray.dir(), samples), ray.time(), ray.wavelength());
+            } else {
+                match hit {
+                    Some(hit) => {
+                        radiance += throughput.component_mul(&spectrum(&hit.material.emitted(), &wavelengths));
+                        // Only the hero wavelength goes the way the ray is bent
+                        if hit.material.is_dispersive() && !wavelengths.secondary_terminated() {
+                            wavelengths.terminate_secondary();
+                            throughput = Vector4::new(throughput[0], 0.0, 0.0, 0.0);
+                        }
+                        throughput.component_mul_assign(&spectrum(&hit.material.attenuation(), &wavelengths).map(|c| c.min(1.0)));
+                        ray = hit.bounce(&ray, samples);
+                    },
+                    None => {
+                        radiance += throughput.component_mul(&spectrum(&world.background(&ray), &wavelengths));
+                        break;
+                    },
+                }
+            }
+            if throughput.max() <= 0.0 {
+                break;
+            }
+            if num_bounces + 1 >= self.roulette_bounces {
+                let survival = throughput.max().clamp(MIN_SURVIVAL, 1.0);
+                if samples.next_1d() >= survival {
+                    break;
+                }
+                throughput /= survival;
+            }
+        }
+        wavelengths.to_rgb(&radiance)
+    }
+}
@@ -0,0 +1,76 @@
+use nalgebra::{Point3, Vector3};
+use crate::integrators::Integrator;
+use crate::media::phase::HenyeyGreenstein;
+use crate::ray::Ray;
+use crate::sampler::SampleStream;
+use crate::world::{World, Hit};
+
+// Whitted-style ray tracer. Specular surfaces reflect what their bounced ray sees, and every other surface
+// is lit only by the light reaching it straight from a point picked on one of the world's lights, so
+// surfaces are black where lights are hidden. Media dim that light, and rays scattered by a medium are lit
+// the same way as surfaces, weighted by the medium's phase function.
+pub struct Whitted();
+
+impl Integrator for Whitted {
+    fn color(&self, world: &World, ray: &Ray, samples: &mut SampleStream, max_bounces: u8) -> Vector3<f32> {
+        let mut color: Vector3<f32> = Vector3::zeros();
+        let mut throughput: Vector3<f32> = Vector3::repeat(1.0);
+        let mut ray = *ray;
+        for _ in 0..max_bounces {
+            let hit = world.hit(&ray);
+            let t_surface = hit.as_ref().map_or(f32::INFINITY, |hit| hit.t);
+            if let Some((event, phase)) = world.medium_event(&ray, t_surface, samples) {
+                color += throughput.component_mul(&event.emission);
+                let light = medium_light(world, &ray, &ray.at(event.t), &phase, samples);
+                return color + throughput.component_mul(&event.albedo).component_mul(&light)
+            }
+            let hit = match hit {
+                Some(hit) => hit,
+                None => return color + throughput.component_mul(&world.background(&ray)),
+            };
+            color += throughput.component_mul(&hit.material.emitted());
+            if !hit.material.is_specular() {
+                return color + throughput.component_mul(&direct_light(world, &ray, &hit, samples))
+            }
+            throughput.component_mul_assign(&hit.material.attenuation());
+            ray = hit.bounce(&ray, samples);
+        }
+        color
+    }
+}
+
+// Light arriving at a hit straight from one point picked on a light, and reflected back along the ray
+pub(crate) fn direct_light(world: &World, ray: &Ray, hit: &Hit, samples: &mut SampleStream) -> Vector3<f32> {
+    let light = match world.sample_light(ray.time(), samples) {
+        Some(light) => light,
+        None => return Vector3::zeros(),
+    };
+    let wo = -ray.dir();
+    let normal = if hit.normal().dot(&wo) < 0.0 { -hit.normal() } else { hit.normal() };
+    let to_light = light.point - hit.point;
+    let distance = to_light.magnitude();
+    let wi = to_light / distance;
+    let (cos_surface, cos_light) = (wi.dot(&normal), -wi.dot(&light.normal));
+    if cos_surface <= 0.0 || cos_light <= 0.0 {
+        return Vector3::zeros()
+    }
+    let transmittance = world.transmittance_between(&hit.point, &light.point, ray.time(), samples);
+    hit.material.eval(&wo, &wi, &normal).component_mul(&light.emitted) * (transmittance * cos_surface * cos_light / (distance.powi(2) * light.pdf))
+}
+
+// Light arriving at a point inside a medium straight from one point picked on a light, and scattered along the ray
+pub(crate) fn medium_light(world: &World, ray: &Ray, point: &Point3<f32>, phase: &HenyeyGreenstein, samples: &mut SampleStream) -> Vector3<f32> {
+    let light = match world.sample_light(ray.time(), samples) {
+        Some(light) => light,
+        None => return Vector3::zeros(),
+    };
+    let to_light = light.point - point;
+    let distance = to_light.magnitude();
+    let wi = to_light / distance;
+    let cos_light = -wi.dot(&light.normal);
+    if cos_light <= 0.0 {
+        return Vector3::zeros()
+    }
+    let transmittance = world.transmittance_between(point, &light.point, ray.time(), samples);
+    light.emitted * (phase.evaluate(ray.dir().dot(&wi)) * transmittance * cos_light / (distance.powi(2) * light.pdf))
+}
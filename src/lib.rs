@@ -1,6 +1,12 @@
+
 pub mod objects;
 pub mod materials;
 pub mod world;
 pub mod camera;
 pub mod ray;
 pub mod ppmhandler;
+pub mod media;
+pub mod sampler;
+pub mod film;
+pub mod integrators;
+pub mod spectrum;
@@ -0,0 +1,117 @@
+use nalgebra::{Vector3, Point3};
+use crate::materials::Material;
+use crate::objects::Object;
+use crate::ray::Ray;
+use crate::sampler::SampleStream;
+
+// Clear material like glass or water that bends rays passing through its surface by its index of refraction
+// and reflects some of them, more at grazing angles. Light passing through is tinted by its color. The
+// legacy tracer shows only what the bent or reflected ray sees.
+pub struct Dielectric {
+    color: Vector3<u8>,
+    ior: f32,
+    dispersion: f32, // Cauchy's B coefficient in square micrometers
+}
+
+const SODIUM_D: f32 = 0.5893; // Wavelength in micrometers the index of refraction is given at
+
+impl Dielectric {
+    pub fn new(color: Vector3<u8>, ior: f32) -> Self {
+        Self::with_dispersion(color, ior, 0.0)
+    }
+
+    // Spectral Requirement
+    // Bends shorter wavelengths more, splitting white light into a rainbow when rendered spectrally. The index of
+    // refraction at a wavelength follows Cauchy's equation n = A + B / lambda^2 with lambda in micrometers, where
+    // dispersion is B and A is set so the index is ior at 589.3nm. Crown glass has a B around 0.004, flint glass
+    // around 0.01. RGB rendering uses ior throughout.
+    pub fn with_dispersion(color: Vector3<u8>, ior: f32, dispersion: f32) -> Self {
+        if ior <= 0.0 {
+            panic!("Index of refraction must be positive, given index of refraction was {}", ior)
+        }
+        if dispersion < 0.0 {
+            panic!("Dispersion must not be negative, given dispersion was {}", dispersion)
+        }
+        Self {
+            color,
+            ior,
+            dispersion,
+        }
+    }
+
+    pub fn get_ior(&self) -> f32 {
+        self.ior
+    }
+
+    pub fn get_dispersion(&self) -> f32 {
+        self.dispersion
+    }
+
+    // Index of refraction for light of the given wavelength in nanometers
+    pub fn ior_at(&self, wavelength: f32) -> f32 {
+        let lambda = wavelength / 1000.0;
+        self.ior + (self.dispersion * ((1.0 / lambda.powi(2)) - (1.0 / SODIUM_D.powi(2))))
+    }
+}
+
+impl Material for Dielectric {
+    fn color(&self) -> Vector3<u8> {
+        self.color
+    }
+
+    fn reflectance(&self) -> f32 {
+        1.0
+    }
+
+    // Rays are reflected with the chance given by Schlick's approximation of the Fresnel equations and
+    // refracted otherwise, which also weights them by that chance
+    fn bounce(&self, ray: &Ray, obj: &dyn Object, pos: &Point3<f32>, intersection: &Point3<f32>, samples: &mut SampleStream) -> Ray {
+        let n = obj.surface_normal(pos, intersection).normalize();
+        let dir = ray.dir();
+        let ior = ray.wavelength().map_or(self.ior, |wavelength| self.ior_at(wavelength));
+        let entering = dir.dot(&n) < 0.0;
+        let (normal, eta) = if entering { (n, 1.0 / ior) } else { (-n, ior) };
+        let cos_i = -dir.dot(&normal);
+        let sin2_t = eta.powi(2) * (1.0 - cos_i.powi(2));
+        let r0 = ((1.0 - ior) / (1.0 + ior)).powi(2);
+        let fresnel = r0 + ((1.0 - r0) * (1.0 - cos_i).powi(5));
+        let u = samples.next_1d();
+        let bounce_dir = if sin2_t > 1.0 || u < fresnel {
+            dir + (2.0 * cos_i * normal)
+        } else {
+            (eta * dir) + (((eta * cos_i) - (1.0 - sin2_t).sqrt()) * normal)
+        };
+        Ray::with_wavelength(*intersection, bounce_dir, ray.time(), ray.wavelength())
+    }
+
+    fn is_specular(&self) -> bool {
+        true
+    }
+
+    fn is_dispersive(&self) -> bool {
+        self.dispersion > 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cauchy_index_matches_ior_at_sodium_d_and_falls_with_wavelength() {
+        let glass = Dielectric::with_dispersion(Vector3::repeat(255), 1.6, 0.01);
+        assert!((glass.ior_at(589.3) - 1.6).abs() < 1e-5);
+        // n = A + B / lambda^2 with A = 1.6 - 0.01 / 0.5893^2
+        assert!((glass.ior_at(450.0) - (1.6 + (0.01 / 0.45_f32.powi(2)) - (0.01 / 0.5893_f32.powi(2)))).abs() < 1e-5);
+        let iors: Vec<f32> = [400.0, 450.0, 500.0, 550.0, 600.0, 650.0, 700.0].iter().map(|lambda| glass.ior_at(*lambda)).collect();
+        assert!(iors.windows(2).all(|pair| pair[0] > pair[1]));
+    }
+
+    #[test]
+    fn no_dispersion_keeps_one_index() {
+        let glass = Dielectric::new(Vector3::repeat(255), 1.5);
+        for lambda in [380.0, 500.0, 720.0] {
+            assert_eq!(glass.ior_at(lambda), 1.5);
+        }
+    }
+}
@@ -1,8 +1,9 @@
 use nalgebra::{Vector3, Point3};
-use rand::{Rng, thread_rng};
+use std::f32::consts::PI;
 use crate::materials::Material;
 use crate::objects::Object;
 use crate::ray::Ray;
+use crate::sampler::SampleStream;
 
 pub struct Diffuse {
     color: Vector3<u8>,
@@ -30,17 +31,55 @@ impl Material for Diffuse {
         self.reflectance
     }
 
-    fn bounce(&self, _ray: &Ray, obj: &Box<dyn Object>, pos: &Point3<f32>, intersection: &Point3<f32>) -> Ray {
-        let bounce_dir: Vector3<f32> = (obj.surface_normal(pos, intersection) + random_unit_vector()).normalize();
-        Ray::new(*intersection, bounce_dir)
+    // Bounces off the side of the surface the ray came from, the same side eval and pdf take the normal on
+    fn bounce(&self, ray: &Ray, obj: &dyn Object, pos: &Point3<f32>, intersection: &Point3<f32>, samples: &mut SampleStream) -> Ray {
+        let n = obj.surface_normal(pos, intersection);
+        let normal = if n.dot(&ray.dir()) > 0.0 { -n } else { n };
+        let bounce_dir: Vector3<f32> = (normal + random_unit_vector(samples.next_2d())).normalize();
+        Ray::with_wavelength(*intersection, bounce_dir, ray.time(), ray.wavelength())
+    }
+
+    // Bounces are spread by the cosine of their angle to the normal, like the light a matte surface reflects
+    fn eval(&self, _wo: &Vector3<f32>, wi: &Vector3<f32>, normal: &Vector3<f32>) -> Vector3<f32> {
+        if wi.dot(normal) <= 0.0 {
+            return Vector3::zeros()
+        }
+        self.attenuation() / PI
+    }
+
+    fn pdf(&self, _wo: &Vector3<f32>, wi: &Vector3<f32>, normal: &Vector3<f32>) -> f32 {
+        wi.dot(normal).max(0.0) / PI
     }
 }
 
-fn random_unit_vector() -> Vector3<f32> {
-    let mut rng = thread_rng();
-    let mut result = Vector3::new(rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0));
-    while result.magnitude() > 1.0 {
-        result = Vector3::new(rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0));
+// Uniformly distributed direction from a point in [0, 1]^2
+fn random_unit_vector((u1, u2): (f32, f32)) -> Vector3<f32> {
+    let z = 1.0 - (2.0 * u1);
+    let r = (1.0 - (z * z)).max(0.0).sqrt();
+    let phi = 2.0 * PI * u2;
+    Vector3::new(r * phi.cos(), r * phi.sin(), z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::plane::Plane;
+    use crate::sampler::independent::Independent;
+
+    // A ray hitting the underside of a floor bounces back down, where eval and pdf also look for it
+    #[test]
+    fn bounces_stay_on_the_side_the_ray_came_from() {
+        let diffuse = Diffuse::new(Vector3::repeat(200), 0.5);
+        let floor = Plane::new(Vector3::new(0.0, 1.0, 0.0));
+        let ray = Ray::new(Point3::new(0.0, -1.0, 0.0), Vector3::new(0.3, 1.0, 0.0));
+        let point = Point3::new(0.3, 0.0, 0.0);
+        let wo = -ray.dir();
+        let normal = Vector3::new(0.0, -1.0, 0.0);
+        for index in 0..64 {
+            let mut samples = SampleStream::new(&Independent(), (0, 0), index, 64, 1);
+            let wi = diffuse.bounce(&ray, &floor, &Point3::origin(), &point, &mut samples).dir();
+            assert!(wi.y < 0.0, "Bounced through the floor along {:?}", wi);
+            assert!(diffuse.pdf(&wo, &wi, &normal) > 0.0 && diffuse.eval(&wo, &wi, &normal).max() > 0.0);
+        }
     }
-    result.normalize()
 }
@@ -0,0 +1,50 @@
+use nalgebra::{Vector3, Point3};
+use crate::materials::Material;
+use crate::objects::Object;
+use crate::ray::Ray;
+use crate::sampler::SampleStream;
+
+// Surface giving off light of its color scaled by strength, without reflecting any. Renders with the
+// legacy tracer show it in its plain color.
+pub struct Emissive {
+    color: Vector3<u8>,
+    strength: f32,
+}
+
+impl Emissive {
+    pub fn new(color: Vector3<u8>, strength: f32) -> Self {
+        if strength <= 0.0 {
+            panic!("Strength must be positive, given strength was {}", strength)
+        }
+        Self {
+            color,
+            strength,
+        }
+    }
+}
+
+impl Material for Emissive {
+    fn color(&self) -> Vector3<u8> {
+        self.color
+    }
+
+    fn reflectance(&self) -> f32 {
+        0.0
+    }
+
+    fn attenuation(&self) -> Vector3<f32> {
+        Vector3::zeros()
+    }
+
+    fn emitted(&self) -> Vector3<f32> {
+        self.strength * self.color.cast::<f32>()
+    }
+
+    fn is_light(&self) -> bool {
+        self.emitted().max() > 0.0
+    }
+
+    fn bounce(&self, ray: &Ray, _obj: &dyn Object, _pos: &Point3<f32>, _intersection: &Point3<f32>, _samples: &mut SampleStream) -> Ray {
+        *ray
+    }
+}
@@ -1,15 +1,59 @@
 use nalgebra::{Point3, Vector3}; 
 use crate::ray::Ray;
 use crate::objects::Object;
+use crate::sampler::SampleStream;
 
 pub mod opaque;
 pub mod diffuse;
 pub mod specular;
+pub mod emissive;
+pub mod dielectric;
 
 pub trait Material {
     fn color(&self) -> Vector3<u8>;
 
     fn reflectance(&self) -> f32;
 
-    fn bounce(&self, ray: &Ray, obj: &Box<dyn Object>, pos: &Point3<f32>, intersection: &Point3<f32>) -> Ray;
+    // Path Tracing Requirement
+    // Fraction of light the surface carries on along bounced rays, per channel. Path tracing uses this
+    // instead of reflectance.
+    fn attenuation(&self) -> Vector3<f32> {
+        self.color().cast::<f32>() / 255.0
+    }
+
+    // Light given off by the surface, on the same scale as colors
+    fn emitted(&self) -> Vector3<f32> {
+        Vector3::zeros()
+    }
+
+    // Whether the surface is a light that integrators may aim rays at, rather than one that only shows its color
+    fn is_light(&self) -> bool {
+        false
+    }
+
+    // Integrator Requirement
+    // Directions point away from the surface, wo towards where the light ends up and wi towards where it comes
+    // from, and normal is on the same side as wo. eval is the fraction of light arriving from wi that leaves
+    // along wo, per unit of solid angle, and pdf is the density of bounce picking wi for a ray arriving along -wo.
+    // Specular surfaces only bounce along a single direction, which neither function covers.
+    fn eval(&self, _wo: &Vector3<f32>, _wi: &Vector3<f32>, _normal: &Vector3<f32>) -> Vector3<f32> {
+        Vector3::zeros()
+    }
+
+    fn pdf(&self, _wo: &Vector3<f32>, _wi: &Vector3<f32>, _normal: &Vector3<f32>) -> f32 {
+        0.0
+    }
+
+    fn is_specular(&self) -> bool {
+        false
+    }
+
+    // Spectral Requirement
+    // Whether bounce sends rays carrying different wavelengths in different directions
+    fn is_dispersive(&self) -> bool {
+        false
+    }
+
+    // Ray leaving the surface after a ray hits it at intersection, using samples for any random choices
+    fn bounce(&self, ray: &Ray, obj: &dyn Object, pos: &Point3<f32>, intersection: &Point3<f32>, samples: &mut SampleStream) -> Ray;
 }
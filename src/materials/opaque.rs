@@ -2,6 +2,7 @@ use nalgebra::{Vector3, Point3};
 use crate::materials::Material;
 use crate::objects::Object;
 use crate::ray::Ray;
+use crate::sampler::SampleStream;
 
 pub struct Opaque {
     color: Vector3<u8>
@@ -24,7 +25,16 @@ impl Material for Opaque {
         0.0
     }
 
-    fn bounce(&self, ray: &Ray, _obj: &Box<dyn Object>, _pos: &Point3<f32>, _intersection: &Point3<f32>) -> Ray {
+    // Opaque surfaces show their color whatever lights them, so they give it off without reflecting anything
+    fn attenuation(&self) -> Vector3<f32> {
+        Vector3::zeros()
+    }
+
+    fn emitted(&self) -> Vector3<f32> {
+        self.color.cast::<f32>()
+    }
+
+    fn bounce(&self, ray: &Ray, _obj: &dyn Object, _pos: &Point3<f32>, _intersection: &Point3<f32>, _samples: &mut SampleStream) -> Ray {
         *ray
     }
 }
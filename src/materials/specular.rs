@@ -2,6 +2,7 @@ use nalgebra::{Vector3, Point3};
 use crate::materials::Material;
 use crate::objects::Object;
 use crate::ray::Ray;
+use crate::sampler::SampleStream;
 
 pub struct Specular {
     color: Vector3<u8>,
@@ -29,8 +30,12 @@ impl Material for Specular {
         self.reflectance
     }
 
-    fn bounce(&self, ray: &Ray, obj: &Box<dyn Object>, pos: &Point3<f32>, intersection: &Point3<f32>) -> Ray {
+    fn bounce(&self, ray: &Ray, obj: &dyn Object, pos: &Point3<f32>, intersection: &Point3<f32>, _samples: &mut SampleStream) -> Ray {
         let n = obj.surface_normal(pos, intersection);
-        Ray::new(*intersection, ray.dir() - (2.0 * n.dot(&ray.dir()) * n))
+        Ray::with_wavelength(*intersection, ray.dir() - (2.0 * n.dot(&ray.dir()) * n), ray.time(), ray.wavelength())
+    }
+
+    fn is_specular(&self) -> bool {
+        true
     }
 }
@@ -0,0 +1,283 @@
+use nalgebra::{Vector3, Point3};
+use std::io::{BufReader, BufRead, Read};
+use std::fs::File;
+use crate::media::{Medium, MediumEvent};
+use crate::media::phase::HenyeyGreenstein;
+use crate::ray::Ray;
+use crate::sampler::SampleStream;
+
+// 3D grid of values stored with x varying fastest, then y, then z
+pub struct VoxelGrid {
+    dim: (usize, usize, usize),
+    data: Vec<f32>,
+}
+
+impl VoxelGrid {
+    pub fn new(dim: (usize, usize, usize), data: Vec<f32>) -> Self {
+        if dim.0 == 0 || dim.1 == 0 || dim.2 == 0 || data.len() != dim.0 * dim.1 * dim.2 {
+            panic!("Voxel grid of size {:?} needs {} values, given {}", dim, dim.0 * dim.1 * dim.2, data.len())
+        }
+        Self {
+            dim,
+            data,
+        }
+    }
+
+    // Reads a grid of little-endian f32 values with no header
+    pub fn from_raw(path: String, dim: (usize, usize, usize)) -> std::io::Result<Self> {
+        let mut bytes: Vec<u8> = vec![];
+        BufReader::new(File::open(path)?).read_to_end(&mut bytes)?;
+        let data = bytes.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect();
+        Ok(Self::new(dim, data))
+    }
+
+    // Reads a text file starting with the dimensions "nx ny nz" followed by whitespace separated
+    // values. Lines starting with # are ignored.
+    pub fn from_ascii(path: String) -> std::io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut dim: Vec<usize> = vec![];
+        let mut data: Vec<f32> = vec![];
+        for line in reader.lines().map_while(Result::ok) {
+            if line.starts_with('#') {
+                continue;
+            }
+            for token in line.split_whitespace() {
+                if dim.len() < 3 {
+                    dim.push(token.parse::<usize>().expect("Error when reading voxel file: dimensions can not be read"));
+                } else {
+                    data.push(token.parse::<f32>().expect("Error when reading voxel file: value can not be read"));
+                }
+            }
+        }
+        if dim.len() < 3 {
+            panic!("Error when reading voxel file: dimensions can not be read");
+        }
+        Ok(Self::new((dim[0], dim[1], dim[2]), data))
+    }
+
+    pub fn dim(&self) -> (usize, usize, usize) {
+        self.dim
+    }
+
+    pub fn max(&self) -> f32 {
+        self.data.iter().fold(0.0, |acc, v| acc.max(*v))
+    }
+
+    fn at(&self, x: usize, y: usize, z: usize) -> f32 {
+        self.data[x + (self.dim.0 * (y + (self.dim.1 * z)))]
+    }
+
+    // Trilinearly interpolates the grid at a point in [0, 1]^3, returning 0 outside of it
+    pub fn sample(&self, p: &Vector3<f32>) -> f32 {
+        if p.iter().any(|v| *v < 0.0 || 1.0 < *v) {
+            return 0.0
+        }
+        let dims = [self.dim.0, self.dim.1, self.dim.2];
+        let mut lo = [0; 3];
+        let mut hi = [0; 3];
+        let mut frac = [0.0; 3];
+        for axis in 0..3 {
+            let g = ((p[axis] * dims[axis] as f32) - 0.5).clamp(0.0, (dims[axis] - 1) as f32);
+            lo[axis] = g.floor() as usize;
+            hi[axis] = (lo[axis] + 1).min(dims[axis] - 1);
+            frac[axis] = g - lo[axis] as f32;
+        }
+        let mut value = 0.0;
+        for corner in 0..8 {
+            let pick = |axis: usize| corner & (1 << axis) != 0;
+            let weight: f32 = (0..3).map(|axis| if pick(axis) { frac[axis] } else { 1.0 - frac[axis] }).product();
+            if weight > 0.0 {
+                value += weight * self.at(
+                    if pick(0) { hi[0] } else { lo[0] },
+                    if pick(1) { hi[1] } else { lo[1] },
+                    if pick(2) { hi[2] } else { lo[2] });
+            }
+        }
+        value
+    }
+}
+
+// Medium with density (and optionally temperature) given by voxel grids stretched over a box centered
+// on the position of its boundary. Interactions are sampled with delta tracking and transmittance is
+// estimated with ratio tracking, both against the largest density in the grid.
+pub struct GridMedium {
+    density: VoxelGrid,
+    temperature: Option<VoxelGrid>, // Kelvin
+    size: Vector3<f32>,
+    sigma_s: f32, // Scattering coefficient at a density of 1
+    sigma_a: f32, // Absorption coefficient at a density of 1
+    color: Vector3<u8>,
+    emission_scale: f32,
+    phase: HenyeyGreenstein,
+    max_density: f32,
+}
+
+impl GridMedium {
+    pub fn new(density: VoxelGrid, size: Vector3<f32>, sigma_s: f32, sigma_a: f32, color: Vector3<u8>, phase: HenyeyGreenstein) -> Self {
+        if sigma_s < 0.0 || sigma_a < 0.0 || sigma_s + sigma_a == 0.0 {
+            panic!("Medium coefficients must be non-negative and not both zero, given coefficients were {} and {}", sigma_s, sigma_a)
+        }
+        let max_density = density.max();
+        Self {
+            density,
+            temperature: None,
+            size,
+            sigma_s,
+            sigma_a,
+            color,
+            emission_scale: 0.0,
+            phase,
+            max_density,
+        }
+    }
+
+    // Medium that glows with blackbody colors where the absorbing part of it is hot. Emission is proportional to
+    // (T / 6500K)^4 times the scale, so a scale of 255 makes 6500K voxels emit full white.
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_temperature(density: VoxelGrid, temperature: VoxelGrid, emission_scale: f32, size: Vector3<f32>, sigma_s: f32, sigma_a: f32, color: Vector3<u8>, phase: HenyeyGreenstein) -> Self {
+        Self {
+            temperature: Some(temperature),
+            emission_scale,
+            ..Self::new(density, size, sigma_s, sigma_a, color, phase)
+        }
+    }
+
+    fn local(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> Vector3<f32> {
+        (pt - pos).component_div(&self.size) + Vector3::repeat(0.5)
+    }
+
+    // Part of [t_min, t_max] the ray spends inside the grid's box, the only part the medium has any density in.
+    // Without this, tracking along an unbounded boundary like a plane would never reach t_max.
+    fn clip(&self, pos: &Point3<f32>, ray: &Ray, t_min: f32, t_max: f32) -> Option<(f32, f32)> {
+        let (mut enter, mut exit) = (t_min, t_max);
+        for axis in 0..3 {
+            let (origin, dir) = (ray.pos()[axis] - pos[axis], ray.dir()[axis]);
+            let half = self.size[axis] / 2.0;
+            if dir == 0.0 {
+                if origin.abs() > half {
+                    return None
+                }
+                continue;
+            }
+            let (near, far) = ((-half - origin) / dir, (half - origin) / dir);
+            enter = enter.max(near.min(far));
+            exit = exit.min(near.max(far));
+        }
+        if enter < exit { Some((enter, exit)) } else { None }
+    }
+
+    fn emission(&self, local: &Vector3<f32>) -> Vector3<f32> {
+        match &self.temperature {
+            Some(grid) => {
+                let kelvin = grid.sample(local);
+                if kelvin <= 0.0 {
+                    return Vector3::zeros()
+                }
+                self.emission_scale * (kelvin / 6500.0).powi(4) * blackbody_color(kelvin)
+            },
+            None => Vector3::zeros(),
+        }
+    }
+}
+
+impl Medium for GridMedium {
+    fn sample_event(&self, pos: &Point3<f32>, ray: &Ray, t_min: f32, t_max: f32, samples: &mut SampleStream) -> Option<MediumEvent> {
+        let majorant = self.max_density * (self.sigma_s + self.sigma_a);
+        if majorant <= 0.0 {
+            return None
+        }
+        let (t_min, t_max) = self.clip(pos, ray, t_min, t_max)?;
+        let mut t = t_min;
+        loop {
+            t -= (1.0 - samples.next_1d()).ln() / majorant;
+            if t >= t_max {
+                return None
+            }
+            let local = self.local(pos, &(ray.pos() + (t * ray.dir())));
+            // Real collision with probability density / max_density, otherwise a null collision
+            if samples.next_1d() * self.max_density < self.density.sample(&local) {
+                let sigma_t = self.sigma_s + self.sigma_a;
+                return Some(MediumEvent {
+                    t,
+                    albedo: (self.sigma_s / sigma_t) * self.color.cast::<f32>() / 255.0,
+                    emission: (self.sigma_a / sigma_t) * self.emission(&local),
+                })
+            }
+        }
+    }
+
+    fn transmittance(&self, pos: &Point3<f32>, ray: &Ray, t_min: f32, t_max: f32, samples: &mut SampleStream) -> f32 {
+        let majorant = self.max_density * (self.sigma_s + self.sigma_a);
+        if majorant <= 0.0 {
+            return 1.0
+        }
+        let (t_min, t_max) = match self.clip(pos, ray, t_min, t_max) {
+            Some(interval) => interval,
+            None => return 1.0,
+        };
+        let mut t = t_min;
+        let mut transmittance = 1.0;
+        loop {
+            t -= (1.0 - samples.next_1d()).ln() / majorant;
+            if t >= t_max {
+                return transmittance
+            }
+            let local = self.local(pos, &(ray.pos() + (t * ray.dir())));
+            transmittance *= 1.0 - (self.density.sample(&local) / self.max_density);
+            if transmittance <= 0.0 {
+                return 0.0
+            }
+        }
+    }
+
+    fn phase(&self) -> HenyeyGreenstein {
+        self.phase
+    }
+}
+
+// Approximate color of a blackbody at a temperature in Kelvin, on a 0 to 255 scale
+fn blackbody_color(kelvin: f32) -> Vector3<f32> {
+    let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+    let r = if t <= 66.0 { 255.0 } else { 329.699 * (t - 60.0).powf(-0.133_204_76) };
+    let g = if t <= 66.0 { (99.470_8 * t.ln()) - 161.119_57 } else { 288.122_16 * (t - 60.0).powf(-0.075_514_85) };
+    let b = if t >= 66.0 { 255.0 } else if t <= 19.0 { 0.0 } else { (138.517_73 * (t - 10.0).ln()) - 305.044_8 };
+    Vector3::new(r, g, b).map(|c| c.clamp(0.0, 255.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sampler::independent::Independent;
+
+    // Density rising from 0.1 to 1 along x over a 2 x 2 x 2 box at the origin
+    fn ramp() -> GridMedium {
+        GridMedium::new(VoxelGrid::new((4, 1, 1), vec![0.1, 0.4, 0.7, 1.0]), Vector3::repeat(2.0), 0.6, 0.4, Vector3::repeat(255), HenyeyGreenstein::isotropic())
+    }
+
+    fn mean_transmittance(medium: &GridMedium, ray: &Ray, t_max: f32) -> f32 {
+        let count = 20000;
+        (0..count)
+            .map(|index| medium.transmittance(&Point3::origin(), ray, 0.0, t_max, &mut SampleStream::new(&Independent(), (0, 0), index, count, 5)))
+            .sum::<f32>() / count as f32
+    }
+
+    #[test]
+    fn ratio_tracking_averages_to_the_exact_transmittance() {
+        let medium = ramp();
+        let ray = Ray::new(Point3::new(-5.0, 0.2, 0.1), Vector3::new(1.0, 0.0, 0.0));
+        let steps = 10000;
+        let depth = (0..steps)
+            .map(|i| medium.density.sample(&medium.local(&Point3::origin(), &Point3::new(-1.0 + (2.0 * (i as f32 + 0.5) / steps as f32), 0.2, 0.1))))
+            .sum::<f32>() * (2.0 / steps as f32) * (medium.sigma_s + medium.sigma_a);
+        let estimate = mean_transmittance(&medium, &ray, f32::INFINITY);
+        assert!((estimate - (-depth).exp()).abs() < 0.01, "Estimated {} for a transmittance of {}", estimate, (-depth).exp());
+    }
+
+    #[test]
+    fn transmittance_outside_the_box_is_one() {
+        let medium = ramp();
+        // Misses the box, and stops before reaching it
+        assert_eq!(mean_transmittance(&medium, &Ray::new(Point3::new(-5.0, 3.0, 0.0), Vector3::new(1.0, 0.0, 0.0)), f32::INFINITY), 1.0);
+        assert_eq!(mean_transmittance(&medium, &Ray::new(Point3::new(-5.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0)), 3.5), 1.0);
+    }
+}
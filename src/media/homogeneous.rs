@@ -0,0 +1,54 @@
+use nalgebra::{Vector3, Point3};
+use crate::media::{Medium, MediumEvent};
+use crate::media::phase::HenyeyGreenstein;
+use crate::ray::Ray;
+use crate::sampler::SampleStream;
+
+// Medium with the same density everywhere, such as fog or a smoke box
+pub struct Homogeneous {
+    sigma_s: f32, // Scattering coefficient, per unit distance
+    sigma_a: f32, // Absorption coefficient, per unit distance
+    color: Vector3<u8>,
+    phase: HenyeyGreenstein,
+}
+
+impl Homogeneous {
+    pub fn new(sigma_s: f32, sigma_a: f32, color: Vector3<u8>, phase: HenyeyGreenstein) -> Self {
+        if sigma_s < 0.0 || sigma_a < 0.0 || sigma_s + sigma_a == 0.0 {
+            panic!("Medium coefficients must be non-negative and not both zero, given coefficients were {} and {}", sigma_s, sigma_a)
+        }
+        Self {
+            sigma_s,
+            sigma_a,
+            color,
+            phase,
+        }
+    }
+}
+
+impl Medium for Homogeneous {
+    fn sample_event(&self, _pos: &Point3<f32>, _ray: &Ray, t_min: f32, t_max: f32, samples: &mut SampleStream) -> Option<MediumEvent> {
+        let sigma_t = self.sigma_s + self.sigma_a;
+        let distance = -(1.0 - samples.next_1d()).ln() / sigma_t;
+        if t_min + distance >= t_max {
+            return None
+        }
+        Some(MediumEvent {
+            t: t_min + distance,
+            albedo: (self.sigma_s / sigma_t) * self.color.cast::<f32>() / 255.0,
+            emission: Vector3::zeros(),
+        })
+    }
+
+    fn transmittance(&self, _pos: &Point3<f32>, _ray: &Ray, t_min: f32, t_max: f32, _samples: &mut SampleStream) -> f32 {
+        let sigma_t = self.sigma_s + self.sigma_a;
+        if sigma_t <= 0.0 {
+            return 1.0
+        }
+        (-sigma_t * (t_max - t_min)).exp()
+    }
+
+    fn phase(&self) -> HenyeyGreenstein {
+        self.phase
+    }
+}
@@ -0,0 +1,26 @@
+use nalgebra::{Vector3, Point3};
+use crate::ray::Ray;
+use crate::sampler::SampleStream;
+use phase::HenyeyGreenstein;
+
+pub mod phase;
+pub mod homogeneous;
+pub mod grid;
+
+// Interaction sampled inside a medium
+pub struct MediumEvent {
+    pub t: f32,
+    pub albedo: Vector3<f32>, // Fraction of light carried on by the scattered ray, per channel
+    pub emission: Vector3<f32>, // Light emitted at the interaction, on the same scale as colors
+}
+
+pub trait Medium {
+    // Samples where a ray travelling through the medium between t_min and t_max interacts with it,
+    // returns None if the ray passes through. pos is the position of the medium's boundary.
+    fn sample_event(&self, pos: &Point3<f32>, ray: &Ray, t_min: f32, t_max: f32, samples: &mut SampleStream) -> Option<MediumEvent>;
+
+    // Fraction of light that makes it through the medium between t_min and t_max
+    fn transmittance(&self, pos: &Point3<f32>, ray: &Ray, t_min: f32, t_max: f32, samples: &mut SampleStream) -> f32;
+
+    fn phase(&self) -> HenyeyGreenstein;
+}
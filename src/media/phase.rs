@@ -0,0 +1,56 @@
+use nalgebra::Vector3;
+use std::f32::consts::PI;
+use crate::sampler::SampleStream;
+
+// Henyey-Greenstein phase function. g ranges from -1 (back scattering) through 0 (isotropic) to 1 (forward scattering).
+#[derive(Copy, Clone)]
+pub struct HenyeyGreenstein {
+    g: f32,
+}
+
+impl HenyeyGreenstein {
+    pub fn new(g: f32) -> Self {
+        if g <= -1.0 || 1.0 <= g {
+            panic!("Asymmetry parameter must be a number between -1 and 1, given g was {}", g)
+        }
+        Self {
+            g,
+        }
+    }
+
+    pub fn isotropic() -> Self {
+        Self::new(0.0)
+    }
+
+    // Probability density of scattering by an angle with the given cosine from the direction of travel
+    pub fn evaluate(&self, cos_theta: f32) -> f32 {
+        let denom = 1.0 + self.g.powi(2) - (2.0 * self.g * cos_theta);
+        (1.0 - self.g.powi(2)) / (4.0 * PI * denom * denom.sqrt())
+    }
+
+    // Samples a new direction of travel for a ray travelling along dir
+    pub fn sample(&self, dir: &Vector3<f32>, samples: &mut SampleStream) -> Vector3<f32> {
+        let (u1, u2) = samples.next_2d();
+        self.sample_with(dir, u1, u2)
+    }
+
+    pub fn sample_with(&self, dir: &Vector3<f32>, u1: f32, u2: f32) -> Vector3<f32> {
+        let cos_theta = if self.g.abs() < 0.001 {
+            1.0 - (2.0 * u1)
+        } else {
+            let s = (1.0 - self.g.powi(2)) / (1.0 - self.g + (2.0 * self.g * u1));
+            (1.0 + self.g.powi(2) - s.powi(2)) / (2.0 * self.g)
+        };
+        let sin_theta = (1.0 - cos_theta.powi(2)).max(0.0).sqrt();
+        let phi = 2.0 * PI * u2;
+        let (t1, t2) = orthonormal_basis(&dir.normalize());
+        (sin_theta * ((phi.cos() * t1) + (phi.sin() * t2))) + (cos_theta * dir.normalize())
+    }
+}
+
+// Returns two unit vectors perpendicular to n and each other
+fn orthonormal_basis(n: &Vector3<f32>) -> (Vector3<f32>, Vector3<f32>) {
+    let helper = if n.x.abs() > 0.9 { Vector3::new(0.0, 1.0, 0.0) } else { Vector3::new(1.0, 0.0, 0.0) };
+    let t1 = n.cross(&helper).normalize();
+    (t1, n.cross(&t1))
+}
@@ -0,0 +1,170 @@
+use nalgebra::{Vector3, Point3};
+use crate::objects::Object;
+use crate::ray::Ray;
+
+const PROBE_DIST: f32 = 0.001;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    Difference, // Left minus right
+}
+
+impl CsgOperation {
+    fn inside(&self, left: bool, right: bool) -> bool {
+        match self {
+            CsgOperation::Union => left || right,
+            CsgOperation::Intersection => left && right,
+            CsgOperation::Difference => left && !right,
+        }
+    }
+}
+
+// Combines two objects into one. Each child is placed at an offset from the position of the node,
+// so nodes can be nested to build up more complicated solids.
+pub struct Csg {
+    operation: CsgOperation,
+    left: (Vector3<f32>, Box<dyn Object>),
+    right: (Vector3<f32>, Box<dyn Object>),
+}
+
+impl Csg {
+    pub fn new(operation: CsgOperation, left: Box<dyn Object>, left_offset: Vector3<f32>, right: Box<dyn Object>, right_offset: Vector3<f32>) -> Self {
+        Self {
+            operation,
+            left: (left_offset, left),
+            right: (right_offset, right),
+        }
+    }
+
+    pub fn union(left: Box<dyn Object>, left_offset: Vector3<f32>, right: Box<dyn Object>, right_offset: Vector3<f32>) -> Self {
+        Self::new(CsgOperation::Union, left, left_offset, right, right_offset)
+    }
+
+    pub fn intersection(left: Box<dyn Object>, left_offset: Vector3<f32>, right: Box<dyn Object>, right_offset: Vector3<f32>) -> Self {
+        Self::new(CsgOperation::Intersection, left, left_offset, right, right_offset)
+    }
+
+    pub fn difference(left: Box<dyn Object>, left_offset: Vector3<f32>, right: Box<dyn Object>, right_offset: Vector3<f32>) -> Self {
+        Self::new(CsgOperation::Difference, left, left_offset, right, right_offset)
+    }
+
+    // Returns the child whose surface the point lies on, along with whether it is the right child.
+    // Each child is probed with a short ray through the point along its own normal, and the child
+    // with a boundary closest to the point wins.
+    fn surface_child(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> (&dyn Object, Point3<f32>, bool) {
+        let mut closest: Option<(&dyn Object, Point3<f32>, bool, f32)> = None;
+        for (is_right, (offset, obj)) in [(false, &self.left), (true, &self.right)] {
+            let child_pos = pos + offset;
+            let normal = obj.surface_normal(&child_pos, pt);
+            let probe = Ray::new(pt - (PROBE_DIST * normal), normal);
+            let miss = obj.intersection_intervals(&child_pos, &probe)
+                .iter()
+                .flat_map(|(enter, exit)| [enter, exit])
+                .map(|t| (t - PROBE_DIST).abs())
+                .fold(f32::INFINITY, f32::min);
+            if closest.map_or(true, |(_, _, _, best)| miss < best) {
+                closest = Some((obj.as_ref(), child_pos, is_right, miss));
+            }
+        }
+        let (obj, child_pos, is_right, _) = closest.unwrap();
+        (obj, child_pos, is_right)
+    }
+}
+
+impl Object for Csg {
+    fn intersection_point(&self, pos: &Point3<f32>, ray: &Ray, tolerance: f32) -> Option<Point3<f32>> {
+        self.intersection_intervals(pos, ray)
+            .into_iter()
+            .flat_map(|(enter, exit)| [enter, exit])
+            .find(|t| *t > tolerance && t.is_finite())
+            .map(|t| ray.at(t))
+    }
+
+    fn surface_normal(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> Vector3<f32> {
+        let (obj, child_pos, is_right) = self.surface_child(pos, pt);
+        let normal = obj.surface_normal(&child_pos, pt);
+        // Surfaces carved out by the right child face into it
+        if is_right && self.operation == CsgOperation::Difference { -normal } else { normal }
+    }
+
+    fn intersection_intervals(&self, pos: &Point3<f32>, ray: &Ray) -> Vec<(f32, f32)> {
+        // Sweeps over every boundary of both children in order, tracking which children the ray is inside
+        let mut events: Vec<(f32, bool, bool)> = vec![]; // (t, is_right, is_entry)
+        for (is_right, (offset, obj)) in [(false, &self.left), (true, &self.right)] {
+            for (enter, exit) in obj.intersection_intervals(&(pos + offset), ray) {
+                events.push((enter, is_right, true));
+                events.push((exit, is_right, false));
+            }
+        }
+        // Entries come before exits at the same t so touching intervals merge and empty ones survive
+        events.sort_by(|a, b| a.0.total_cmp(&b.0).then(b.2.cmp(&a.2)));
+
+        let mut intervals: Vec<(f32, f32)> = vec![];
+        let mut depth = (0, 0);
+        let mut start: Option<f32> = None;
+        for (t, is_right, is_entry) in events {
+            let change = if is_entry { 1 } else { -1 };
+            if is_right { depth.1 += change } else { depth.0 += change }
+            let inside = self.operation.inside(depth.0 > 0, depth.1 > 0);
+            match start {
+                None if inside => start = Some(t),
+                Some(enter) if !inside => {
+                    intervals.push((enter, t));
+                    start = None;
+                },
+                _ => { },
+            }
+        }
+        intervals
+    }
+
+    fn surface_uv(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> Option<(f32, f32)> {
+        let (obj, child_pos, _) = self.surface_child(pos, pt);
+        obj.surface_uv(&child_pos, pt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::sphere::Sphere;
+
+    // Two unit spheres overlapping along the x axis, crossed by a ray along it that enters the left one at t = 3.5
+    fn intervals(operation: CsgOperation, right_offset: Vector3<f32>) -> Vec<(f32, f32)> {
+        let csg = Csg::new(operation, Box::new(Sphere::new(1.0)), Vector3::new(-0.5, 0.0, 0.0), Box::new(Sphere::new(1.0)), right_offset);
+        let ray = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        csg.intersection_intervals(&Point3::origin(), &ray)
+    }
+
+    fn assert_intervals(intervals: Vec<(f32, f32)>, expected: &[(f32, f32)]) {
+        assert_eq!(intervals.len(), expected.len(), "Intervals were {:?}", intervals);
+        for ((enter, exit), (expected_enter, expected_exit)) in intervals.iter().zip(expected) {
+            assert!((enter - expected_enter).abs() < 0.001 && (exit - expected_exit).abs() < 0.001, "Intervals were {:?}", intervals);
+        }
+    }
+
+    #[test]
+    fn union_merges_overlapping_intervals() {
+        assert_intervals(intervals(CsgOperation::Union, Vector3::new(0.5, 0.0, 0.0)), &[(3.5, 6.5)]);
+        assert_intervals(intervals(CsgOperation::Union, Vector3::new(2.5, 0.0, 0.0)), &[(3.5, 5.5), (6.5, 8.5)]);
+    }
+
+    #[test]
+    fn intersection_keeps_the_overlap() {
+        assert_intervals(intervals(CsgOperation::Intersection, Vector3::new(0.5, 0.0, 0.0)), &[(4.5, 5.5)]);
+        assert_intervals(intervals(CsgOperation::Intersection, Vector3::new(2.5, 0.0, 0.0)), &[]);
+    }
+
+    #[test]
+    fn difference_cuts_out_the_right_child() {
+        assert_intervals(intervals(CsgOperation::Difference, Vector3::new(0.5, 0.0, 0.0)), &[(3.5, 4.5)]);
+        // A smaller sphere inside splits the left one into a shell crossed twice
+        let csg = Csg::difference(Box::new(Sphere::new(1.0)), Vector3::zeros(), Box::new(Sphere::new(0.5)), Vector3::zeros());
+        let ray = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        assert_intervals(csg.intersection_intervals(&Point3::origin(), &ray), &[(4.0, 4.5), (5.5, 6.0)]);
+        let hit = csg.intersection_point(&Point3::origin(), &Ray::new(Point3::origin(), Vector3::new(1.0, 0.0, 0.0)), 0.001);
+        assert!(hit.is_some_and(|hit| (hit.x - 0.5).abs() < 0.001));
+    }
+}
@@ -0,0 +1,93 @@
+use nalgebra::{Vector3, Point3, UnitQuaternion};
+use std::f32::consts::PI;
+use crate::objects::Object;
+use crate::ray::Ray;
+
+pub struct Cylinder { // Capped cylinder centered on its position
+    radius: f32,
+    height: f32,
+    rotation: UnitQuaternion<f32>, // Rotates from cylinder space (axis along +y) to world space
+}
+
+impl Cylinder {
+    pub fn new(radius: f32, height: f32) -> Self {
+        Self::with_axis(radius, height, Vector3::new(0.0, 1.0, 0.0))
+    }
+
+    pub fn with_axis(radius: f32, height: f32, axis: Vector3<f32>) -> Self {
+        if radius <= 0.0 || height <= 0.0 {
+            panic!("Cylinder radius and height must be positive, given radius was {} and height was {}", radius, height)
+        }
+        let rotation = UnitQuaternion::rotation_between(&Vector3::new(0.0, 1.0, 0.0), &axis.normalize())
+            .unwrap_or_else(|| UnitQuaternion::from_axis_angle(&Vector3::x_axis(), PI));
+        Self {
+            radius,
+            height,
+            rotation,
+        }
+    }
+}
+
+impl Object for Cylinder {
+    fn intersection_point(&self, pos: &Point3<f32>, ray: &Ray, tolerance: f32) -> Option<Point3<f32>> {
+        self.intersection_intervals(pos, ray)
+            .into_iter()
+            .flat_map(|(enter, exit)| [enter, exit])
+            .find(|t| *t > tolerance)
+            .map(|t| ray.at(t))
+    }
+
+    fn surface_normal(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> Vector3<f32> {
+        let p = self.rotation.inverse() * (pt - pos);
+        let side = Vector3::new(p.x, 0.0, p.z);
+        let to_side = (side.magnitude() - self.radius).abs();
+        let to_cap = (p.y.abs() - (self.height / 2.0)).abs();
+        if to_cap < to_side || side.magnitude() == 0.0 {
+            self.rotation * Vector3::new(0.0, p.y.signum(), 0.0)
+        } else {
+            self.rotation * side.normalize()
+        }
+    }
+
+    fn intersection_intervals(&self, pos: &Point3<f32>, ray: &Ray) -> Vec<(f32, f32)> {
+        let o = self.rotation.inverse() * (ray.pos() - pos);
+        let d = self.rotation.inverse() * ray.dir();
+
+        // Infinite tube around the axis
+        let a = d.x.powi(2) + d.z.powi(2);
+        let c = o.x.powi(2) + o.z.powi(2) - self.radius.powi(2);
+        let tube = if a == 0.0 {
+            if c >= 0.0 {
+                return vec![]
+            }
+            (f32::NEG_INFINITY, f32::INFINITY)
+        } else {
+            let b = (o.x * d.x) + (o.z * d.z);
+            let disc = b.powi(2) - (a * c);
+            if disc < 0.0 {
+                return vec![]
+            }
+            ((-b - disc.sqrt()) / a, (-b + disc.sqrt()) / a)
+        };
+
+        // Slab between the caps
+        let half = self.height / 2.0;
+        let slab = if d.y == 0.0 {
+            if o.y.abs() > half {
+                return vec![]
+            }
+            (f32::NEG_INFINITY, f32::INFINITY)
+        } else {
+            let t1 = (-half - o.y) / d.y;
+            let t2 = (half - o.y) / d.y;
+            (t1.min(t2), t1.max(t2))
+        };
+
+        let enter = tube.0.max(slab.0);
+        let exit = tube.1.min(slab.1);
+        if enter > exit {
+            return vec![]
+        }
+        vec![(enter, exit)]
+    }
+}
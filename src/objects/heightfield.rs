@@ -0,0 +1,254 @@
+use nalgebra::{Vector3, Point3};
+use crate::objects::Object;
+use crate::ppmhandler::{PPMHandler, PPMImage};
+use crate::ray::Ray;
+
+const EPS: f32 = 0.00001;
+
+// Terrain from a grid of heights. The grid is centered on the object position in the xz-plane, with
+// heights[x][z] giving the height of each sample (matching the column layout of PPMImage). Each cell
+// between four samples is split into two triangles and traced with a 2D DDA over the cells.
+pub struct Heightfield {
+    heights: Vec<Vec<f32>>,
+    normals: Vec<Vec<Vector3<f32>>>, // Per-sample normals, interpolated across cells for smooth shading
+    spacing: (f32, f32),
+    size: (f32, f32),
+    bounds: (f32, f32), // Lowest and highest sample
+}
+
+impl Heightfield {
+    pub fn new(heights: Vec<Vec<f32>>, width: f32, depth: f32) -> Self {
+        let nx = heights.len();
+        let nz = heights.first().map_or(0, |column| column.len());
+        if nx < 2 || nz < 2 || heights.iter().any(|column| column.len() != nz) {
+            panic!("Heightfield needs a rectangular grid of at least 2 x 2 samples")
+        }
+        let spacing = (width / (nx - 1) as f32, depth / (nz - 1) as f32);
+        let normals = (0..nx).map(|x| (0..nz).map(|z| {
+            let dx = (heights[(x + 1).min(nx - 1)][z] - heights[x.saturating_sub(1)][z]) / (((x + 1).min(nx - 1) - x.saturating_sub(1)) as f32 * spacing.0);
+            let dz = (heights[x][(z + 1).min(nz - 1)] - heights[x][z.saturating_sub(1)]) / (((z + 1).min(nz - 1) - z.saturating_sub(1)) as f32 * spacing.1);
+            Vector3::new(-dx, 1.0, -dz).normalize()
+        }).collect()).collect();
+        let bounds = heights.iter().flatten().fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), h| (lo.min(*h), hi.max(*h)));
+        Self {
+            heights,
+            normals,
+            spacing,
+            size: (width, depth),
+            bounds,
+        }
+    }
+
+    // Uses the brightness of each pixel as its height, scaled so the maximum value maps to max_height
+    pub fn from_image(image: &PPMImage, width: f32, depth: f32, max_height: f32) -> Self {
+        let (iwidth, iheight) = image.dim();
+        let heights = (0..iwidth).map(|x| (0..iheight).map(|y| {
+            let pixel = image.pixel_at(x, y);
+            max_height * (pixel.x + pixel.y + pixel.z) as f32 / (3.0 * image.maxval() as f32)
+        }).collect()).collect();
+        Self::new(heights, width, depth)
+    }
+
+    pub fn from_ppm(path: String, width: f32, depth: f32, max_height: f32) -> std::io::Result<Self> {
+        let image = PPMHandler().read(&path)?;
+        Ok(Self::from_image(&image, width, depth, max_height))
+    }
+
+    fn cells(&self) -> (usize, usize) {
+        (self.heights.len() - 1, self.heights[0].len() - 1)
+    }
+
+    // Returns the cell containing a local xz position along with the position within the cell in [0, 1]
+    fn cell_at(&self, x: f32, z: f32) -> ((usize, usize), (f32, f32)) {
+        let (cx, cz) = self.cells();
+        let gx = (x + (self.size.0 / 2.0)) / self.spacing.0;
+        let gz = (z + (self.size.1 / 2.0)) / self.spacing.1;
+        let ix = (gx.floor().max(0.0) as usize).min(cx - 1);
+        let iz = (gz.floor().max(0.0) as usize).min(cz - 1);
+        ((ix, iz), (gx - ix as f32, gz - iz as f32))
+    }
+
+    // Plane of one of the triangles in a cell as (h00, slope along u, slope along v). The lower triangle
+    // covers u >= v and the upper one u <= v.
+    fn triangle_plane(&self, (ix, iz): (usize, usize), upper: bool) -> (f32, f32, f32) {
+        let h00 = self.heights[ix][iz];
+        let h10 = self.heights[ix + 1][iz];
+        let h01 = self.heights[ix][iz + 1];
+        let h11 = self.heights[ix + 1][iz + 1];
+        if upper {
+            (h00, h11 - h01, h01 - h00)
+        } else {
+            (h00, h10 - h00, h11 - h10)
+        }
+    }
+
+    fn height_at(&self, x: f32, z: f32) -> f32 {
+        let (cell, (u, v)) = self.cell_at(x, z);
+        let (h, du, dv) = self.triangle_plane(cell, u < v);
+        h + (du * u) + (dv * v)
+    }
+
+    // Walks the cells the ray passes over between t_start and t_end, calling visit with each surface
+    // crossing in order until it returns false
+    fn traverse(&self, o: &Vector3<f32>, d: &Vector3<f32>, t_start: f32, t_end: f32, mut visit: impl FnMut(f32) -> bool) {
+        let (cx, cz) = self.cells();
+        let start = o + (t_start * d);
+        let ((mut ix, mut iz), _) = self.cell_at(start.x, start.z);
+        let origin = (-self.size.0 / 2.0, -self.size.1 / 2.0);
+        let next_boundary = |i: usize, dir: f32, spacing: f32, lo: f32, o: f32| -> f32 {
+            if dir > 0.0 {
+                (lo + ((i + 1) as f32 * spacing) - o) / dir
+            } else if dir < 0.0 {
+                (lo + (i as f32 * spacing) - o) / dir
+            } else {
+                f32::INFINITY
+            }
+        };
+        let mut t_max = (
+            next_boundary(ix, d.x, self.spacing.0, origin.0, o.x),
+            next_boundary(iz, d.z, self.spacing.1, origin.1, o.z));
+        let t_delta = (self.spacing.0 / d.x.abs(), self.spacing.1 / d.z.abs());
+        let mut t_enter = t_start;
+        let mut last: Option<f32> = None;
+        loop {
+            let t_exit = t_max.0.min(t_max.1).min(t_end);
+            let y_range = ((o.y + (t_enter * d.y)).min(o.y + (t_exit * d.y)), (o.y + (t_enter * d.y)).max(o.y + (t_exit * d.y)));
+            let corners = [self.heights[ix][iz], self.heights[ix + 1][iz], self.heights[ix][iz + 1], self.heights[ix + 1][iz + 1]];
+            let cell_range = corners.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), h| (lo.min(*h), hi.max(*h)));
+            if y_range.0 <= cell_range.1 && cell_range.0 <= y_range.1 {
+                let mut hits: Vec<f32> = [false, true].iter()
+                    .filter_map(|upper| self.cell_crossing(o, d, (ix, iz), *upper, t_enter, t_exit))
+                    .collect();
+                hits.sort_by(|a, b| a.total_cmp(b));
+                for t in hits {
+                    // Crossings on a shared edge are found by both triangles
+                    if last.is_some_and(|prev| (t - prev).abs() < EPS) {
+                        continue;
+                    }
+                    last = Some(t);
+                    if !visit(t) {
+                        return;
+                    }
+                }
+            }
+            if t_exit >= t_end {
+                return;
+            }
+            if t_max.0 < t_max.1 {
+                if (d.x > 0.0 && ix + 1 >= cx) || (d.x < 0.0 && ix == 0) {
+                    return;
+                }
+                ix = if d.x > 0.0 { ix + 1 } else { ix - 1 };
+                t_max.0 += t_delta.0;
+            } else {
+                if (d.z > 0.0 && iz + 1 >= cz) || (d.z < 0.0 && iz == 0) {
+                    return;
+                }
+                iz = if d.z > 0.0 { iz + 1 } else { iz - 1 };
+                t_max.1 += t_delta.1;
+            }
+            t_enter = t_exit;
+        }
+    }
+
+    fn cell_crossing(&self, o: &Vector3<f32>, d: &Vector3<f32>, cell: (usize, usize), upper: bool, t_enter: f32, t_exit: f32) -> Option<f32> {
+        let (h, du, dv) = self.triangle_plane(cell, upper);
+        let x0 = (-self.size.0 / 2.0) + (cell.0 as f32 * self.spacing.0);
+        let z0 = (-self.size.1 / 2.0) + (cell.1 as f32 * self.spacing.1);
+        // Solve o.y + t*d.y = h + du*u(t) + dv*v(t) with u and v linear in t
+        let (u0, v0) = ((o.x - x0) / self.spacing.0, (o.z - z0) / self.spacing.1);
+        let (ut, vt) = (d.x / self.spacing.0, d.z / self.spacing.1);
+        let rate = d.y - (du * ut) - (dv * vt);
+        if rate == 0.0 {
+            return None
+        }
+        let t = (h + (du * u0) + (dv * v0) - o.y) / rate;
+        if t < t_enter - EPS || t > t_exit + EPS {
+            return None
+        }
+        let (u, v) = (u0 + (t * ut), v0 + (t * vt));
+        let inside_cell = (-EPS..=1.0 + EPS).contains(&u) && (-EPS..=1.0 + EPS).contains(&v);
+        let inside_triangle = if upper { u <= v + EPS } else { u + EPS >= v };
+        if inside_cell && inside_triangle { Some(t) } else { None }
+    }
+
+    // Clips the ray against the bounding box of the terrain
+    fn clip(&self, o: &Vector3<f32>, d: &Vector3<f32>) -> Option<(f32, f32)> {
+        let lo = Vector3::new(-self.size.0 / 2.0, self.bounds.0, -self.size.1 / 2.0);
+        let hi = Vector3::new(self.size.0 / 2.0, self.bounds.1, self.size.1 / 2.0);
+        let mut range = (f32::NEG_INFINITY, f32::INFINITY);
+        for axis in 0..3 {
+            if d[axis] == 0.0 {
+                if o[axis] < lo[axis] || o[axis] > hi[axis] {
+                    return None
+                }
+                continue;
+            }
+            let t1 = (lo[axis] - o[axis]) / d[axis];
+            let t2 = (hi[axis] - o[axis]) / d[axis];
+            range = (range.0.max(t1.min(t2)), range.1.min(t1.max(t2)));
+        }
+        if range.0 > range.1 { None } else { Some(range) }
+    }
+}
+
+impl Object for Heightfield {
+    fn intersection_point(&self, pos: &Point3<f32>, ray: &Ray, tolerance: f32) -> Option<Point3<f32>> {
+        let o = ray.pos() - pos;
+        let d = ray.dir();
+        let (t_min, t_max) = self.clip(&o, &d)?;
+        if t_max <= tolerance {
+            return None
+        }
+        let mut hit: Option<f32> = None;
+        self.traverse(&o, &d, t_min.max(tolerance), t_max, |t| {
+            if t > tolerance {
+                hit = Some(t);
+                return false
+            }
+            true
+        });
+        hit.map(|t| ray.at(t))
+    }
+
+    // Interpolates the sample normals across the triangle containing the point
+    fn surface_normal(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> Vector3<f32> {
+        let p = pt - pos;
+        let ((ix, iz), (u, v)) = self.cell_at(p.x, p.z);
+        let (u, v) = (u.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+        let n00 = self.normals[ix][iz];
+        let n11 = self.normals[ix + 1][iz + 1];
+        if u < v {
+            (((1.0 - v) * n00) + (u * n11) + ((v - u) * self.normals[ix][iz + 1])).normalize()
+        } else {
+            (((1.0 - u) * n00) + ((u - v) * self.normals[ix + 1][iz]) + (v * n11)).normalize()
+        }
+    }
+
+    // The inside of a heightfield is the volume between its surface and the bottom of its bounds
+    fn intersection_intervals(&self, pos: &Point3<f32>, ray: &Ray) -> Vec<(f32, f32)> {
+        let o = ray.pos() - pos;
+        let d = ray.dir();
+        let Some((t_min, t_max)) = self.clip(&o, &d) else { return vec![] };
+        let below = |t: f32| {
+            let p = o + (t * d);
+            p.y < self.height_at(p.x, p.z)
+        };
+        let mut intervals: Vec<(f32, f32)> = vec![];
+        let mut start: Option<f32> = if below(t_min + EPS) { Some(t_min) } else { None };
+        self.traverse(&o, &d, t_min, t_max, |t| {
+            match start {
+                Some(enter) => {
+                    intervals.push((enter, t));
+                    start = None;
+                },
+                None => start = Some(t),
+            }
+            true
+        });
+        if let Some(enter) = start {
+            intervals.push((enter, t_max));
+        }
+        intervals
+    }
+}
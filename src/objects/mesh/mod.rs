@@ -17,13 +17,11 @@ impl Mesh {
         let lines = reader.lines();
         let mut vertices: Vec<Vector3<f32>> = vec![];
         let mut triangles: Vec<Triangle> = vec![];
-        for line_result in lines {
-            if let Ok(line) = line_result {
-                if line.starts_with("#") {
-                    continue;
-                } else {
-                   Self::read_line(&line, &mut vertices, &mut triangles); 
-                }
+        for line in lines.map_while(Result::ok) {
+            if line.starts_with('#') {
+                continue;
+            } else {
+               Self::read_line(&line, &mut vertices, &mut triangles); 
             }
         }
         Ok(Self {
@@ -31,7 +29,7 @@ impl Mesh {
         })
     }
 
-    fn read_line(line: &str, vertices: &mut Vec<Vector3<f32>>, triangles: &mut Vec<Triangle>) -> () {
+    fn read_line(line: &str, vertices: &mut Vec<Vector3<f32>>, triangles: &mut Vec<Triangle>) {
         if line.starts_with("#") {
         } else if line.starts_with("v") {
             let mut coords = line.split_whitespace();
@@ -53,7 +51,7 @@ impl Mesh {
 
 impl Object for Mesh {
     fn surface_normal(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> Vector3<f32> {
-        let mut closest = (self.triangles.get(0).expect("MeshError: No triangles in mesh"), self.triangles.get(0).expect("MeshError: No triangles in mesh").distance_to_point(pos, pt));
+        let mut closest = (self.triangles.first().expect("MeshError: No triangles in mesh"), self.triangles.first().expect("MeshError: No triangles in mesh").distance_to_point(pos, pt));
         for tri in self.triangles.iter() {
             let tri_distance = tri.distance_to_point(pos, pt);
             if tri_distance < closest.1 {
@@ -66,21 +64,39 @@ impl Object for Mesh {
     fn intersection_point(&self, pos: &Point3<f32>, ray: &Ray, tolerance: f32) -> Option<Point3<f32>> {
         let mut closest: Option<Point3<f32>> = None;
         let intersections: Vec<Option<Point3<f32>>> = self.triangles.iter().map(|tri| tri.intersection_point(pos, ray, tolerance)).collect();
-        for intersection in intersections.iter() {
-            match intersection {
-                Some(point) => {
-                    match closest {
-                        Some(prev) => {
-                            if (ray.pos() - point).magnitude() < (ray.pos() - prev).magnitude() {
-                                closest = Some(*point);
-                            }
-                        }
-                        None => closest = Some(*point),
+        for point in intersections.iter().flatten() {
+            match closest {
+                Some(prev) => {
+                    if (ray.pos() - point).magnitude() < (ray.pos() - prev).magnitude() {
+                        closest = Some(*point);
                     }
-                },
-                None => { },
+                }
+                None => closest = Some(*point),
             }
         }
         closest
     }
+
+    // Treats the mesh as closed with its triangles facing out, so the ray enters through triangles facing it and
+    // leaves through ones facing away. A crossing missed at an edge or a hole in the mesh leaves an entry or exit
+    // without a partner, which is dropped rather than letting the inside run on forever.
+    fn intersection_intervals(&self, pos: &Point3<f32>, ray: &Ray) -> Vec<(f32, f32)> {
+        let mut crossings: Vec<(f32, bool)> = self.triangles.iter()
+            .filter_map(|tri| tri.intersection_t(pos, ray).map(|t| (t, tri.surface_normal(pos, &ray.at(t)).dot(&ray.dir()) < 0.0)))
+            .collect();
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut intervals = vec![];
+        let mut entry = None;
+        for (t, entering) in crossings {
+            match (entering, entry) {
+                (true, None) => entry = Some(t),
+                (false, Some(enter)) => {
+                    intervals.push((enter, t));
+                    entry = None;
+                },
+                _ => (),
+            }
+        }
+        intervals
+    }
 }
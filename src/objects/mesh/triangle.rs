@@ -32,19 +32,9 @@ impl Triangle {
         }
         distance_from_point_to_line(point, &p1, &p2).min(distance_from_point_to_line(point, &p2, &p3).min(distance_from_point_to_line(point, &p3, &p1)))
     }
-}
-
-fn distance_from_point_to_line(p: &Point3<f32>, a: &Point3<f32>, b: &Point3<f32>) -> f32 {
-    if (b - a).dot(&(p - a)) <= 0.0 {
-        return (p - a).magnitude()
-    } else if (p - b).dot(&(b - a)) >= 0.0 {
-        return (p - b).magnitude()
-    }
-    (b - a).cross(&(p - a)).magnitude() / (b - a).magnitude()
-}
 
-impl Object for Triangle {
-    fn intersection_point(&self, pos: &Point3<f32>, ray: &Ray, tolerance: f32) -> Option<Point3<f32>> {
+    // Returns the ray parameter of the intersection, including intersections behind the ray origin
+    pub fn intersection_t(&self, pos: &Point3<f32>, ray: &Ray) -> Option<f32> {
         let p1 = pos + self.v1;
         let p2 = pos + self.v2;
         let p3 = pos + self.v3;
@@ -53,19 +43,39 @@ impl Object for Triangle {
         }
         let d = -self.snorm.dot(&p1.coords); 
         let t = -(self.snorm.dot(&ray.pos().coords) + d) / (self.snorm.dot(&ray.dir()));
-        if t <= tolerance {
-            return None
-        }
-        let p = ray.at(t);
+        let p = ray.pos() + (t * ray.dir());
         if self.snorm.dot(&(p2 - p1).cross(&(p - p1))) > 0.0 && 
         self.snorm.dot(&(p3 - p2).cross(&(p - p2))) > 0.0 && 
         self.snorm.dot(&(p1 - p3).cross(&(p - p3))) > 0.0 {
-            return Some(p)
+            return Some(t)
         }
         None
     }
+}
+
+fn distance_from_point_to_line(p: &Point3<f32>, a: &Point3<f32>, b: &Point3<f32>) -> f32 {
+    if (b - a).dot(&(p - a)) <= 0.0 {
+        return (p - a).magnitude()
+    } else if (p - b).dot(&(b - a)) >= 0.0 {
+        return (p - b).magnitude()
+    }
+    (b - a).cross(&(p - a)).magnitude() / (b - a).magnitude()
+}
+
+impl Object for Triangle {
+    fn intersection_point(&self, pos: &Point3<f32>, ray: &Ray, tolerance: f32) -> Option<Point3<f32>> {
+        match self.intersection_t(pos, ray) {
+            Some(t) if t > tolerance => Some(ray.at(t)),
+            _ => None,
+        }
+    }
 
     fn surface_normal(&self, _pos: &Point3<f32>, _pt: &Point3<f32>) -> Vector3<f32> {
         self.snorm
     }
+
+    // A lone triangle is flat and has no inside
+    fn intersection_intervals(&self, _pos: &Point3<f32>, _ray: &Ray) -> Vec<(f32, f32)> {
+        vec![]
+    }
 }
@@ -4,10 +4,38 @@ use crate::ray::Ray;
 pub mod sphere;
 pub mod mesh;
 pub mod plane;
+pub mod torus;
+pub mod quartic;
+pub mod cylinder;
+pub mod csg;
+pub mod sdf;
+pub mod heightfield;
+pub mod motion;
 
 pub trait Object {
     // Returns closest intersection point if there is one (otherwise returns None)
     fn intersection_point(&self, pos: &Point3<f32>, ray: &Ray, tolerance: f32) -> Option<Point3<f32>>;
 
     fn surface_normal(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> Vector3<f32>;
+
+    // Returns every (entry, exit) pair of ray parameters t where the ray is inside the object, in ascending order.
+    // Entries may be negative when the ray starts inside the object and bounds may be infinite for unbounded objects.
+    // Objects without an inside (e.g. single triangles) return no intervals.
+    fn intersection_intervals(&self, pos: &Point3<f32>, ray: &Ray) -> Vec<(f32, f32)>;
+
+    // Returns texture coordinates in [0, 1] x [0, 1] for a point on the surface, if the object has a parameterization
+    fn surface_uv(&self, _pos: &Point3<f32>, _pt: &Point3<f32>) -> Option<(f32, f32)> {
+        None
+    }
+
+    // Returns a point on the surface picked from a point in [0, 1]^2, its surface normal and the probability
+    // density of picking it per unit of area, if the object supports it. Emissive objects need this to be used as lights.
+    fn sample_surface(&self, _pos: &Point3<f32>, _u: (f32, f32)) -> Option<(Point3<f32>, Vector3<f32>, f32)> {
+        None
+    }
+
+    // Probability density per unit of area of sample_surface picking a point on the surface, 0 if it isn't supported
+    fn surface_pdf(&self, _pos: &Point3<f32>, _pt: &Point3<f32>) -> f32 {
+        0.0
+    }
 }
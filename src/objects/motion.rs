@@ -0,0 +1,100 @@
+use nalgebra::{Vector3, Point3, Isometry3, Translation3};
+use crate::objects::Object;
+use crate::ray::Ray;
+
+// Movement of an object over time as keyframed transforms. Each transform rotates the object about its
+// position and then translates it. Rotations are interpolated spherically and translations linearly,
+// and the object holds its first and last transforms before and after the keyframes.
+pub struct Motion {
+    keyframes: Vec<(f32, Isometry3<f32>)>,
+}
+
+impl Motion {
+    // Moves between two transforms from time 0 to time 1
+    pub fn linear(start: Isometry3<f32>, end: Isometry3<f32>) -> Self {
+        Self::keyframed(vec![(0.0, start), (1.0, end)])
+    }
+
+    // Moves by an offset from time 0 to time 1 without rotating
+    pub fn translation(offset: Vector3<f32>) -> Self {
+        Self::linear(Isometry3::identity(), Isometry3::from_parts(Translation3::from(offset), Default::default()))
+    }
+
+    pub fn keyframed(mut keyframes: Vec<(f32, Isometry3<f32>)>) -> Self {
+        if keyframes.is_empty() {
+            panic!("Motion needs at least one keyframe")
+        }
+        keyframes.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self {
+            keyframes,
+        }
+    }
+
+    pub fn transform_at(&self, time: f32) -> Isometry3<f32> {
+        let first = self.keyframes[0];
+        let last = self.keyframes[self.keyframes.len() - 1];
+        if time <= first.0 {
+            return first.1
+        } else if time >= last.0 {
+            return last.1
+        }
+        let next = self.keyframes.iter().position(|(t, _)| *t > time).unwrap();
+        let (t0, a) = self.keyframes[next - 1];
+        let (t1, b) = self.keyframes[next];
+        a.lerp_slerp(&b, (time - t0) / (t1 - t0))
+    }
+}
+
+// View of an object frozen at one transform of its motion
+pub struct Posed<'a> {
+    object: &'a dyn Object,
+    transform: Isometry3<f32>,
+}
+
+impl<'a> Posed<'a> {
+    pub fn new(object: &'a dyn Object, motion: &Motion, time: f32) -> Self {
+        Self {
+            object,
+            transform: motion.transform_at(time),
+        }
+    }
+
+    fn to_rest(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> Point3<f32> {
+        pos + self.transform.inverse_transform_point(&Point3::from(pt - pos)).coords
+    }
+
+    fn to_posed(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> Point3<f32> {
+        pos + (self.transform * Point3::from(pt - pos)).coords
+    }
+
+    fn ray_to_rest(&self, pos: &Point3<f32>, ray: &Ray) -> Ray {
+        Ray::with_wavelength(self.to_rest(pos, &ray.pos()), self.transform.inverse_transform_vector(&ray.dir()), ray.time(), ray.wavelength())
+    }
+}
+
+// Transforms preserve distances, so ray parameters are the same in rest and posed space
+impl<'a> Object for Posed<'a> {
+    fn intersection_point(&self, pos: &Point3<f32>, ray: &Ray, tolerance: f32) -> Option<Point3<f32>> {
+        self.object.intersection_point(pos, &self.ray_to_rest(pos, ray), tolerance).map(|pt| self.to_posed(pos, &pt))
+    }
+
+    fn surface_normal(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> Vector3<f32> {
+        self.transform * self.object.surface_normal(pos, &self.to_rest(pos, pt))
+    }
+
+    fn intersection_intervals(&self, pos: &Point3<f32>, ray: &Ray) -> Vec<(f32, f32)> {
+        self.object.intersection_intervals(pos, &self.ray_to_rest(pos, ray))
+    }
+
+    fn surface_uv(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> Option<(f32, f32)> {
+        self.object.surface_uv(pos, &self.to_rest(pos, pt))
+    }
+
+    fn sample_surface(&self, pos: &Point3<f32>, u: (f32, f32)) -> Option<(Point3<f32>, Vector3<f32>, f32)> {
+        self.object.sample_surface(pos, u).map(|(pt, normal, pdf)| (self.to_posed(pos, &pt), self.transform * normal, pdf))
+    }
+
+    fn surface_pdf(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> f32 {
+        self.object.surface_pdf(pos, &self.to_rest(pos, pt))
+    }
+}
@@ -26,4 +26,19 @@ impl Object for Plane {
     fn surface_normal(&self, _pos: &Point3<f32>, _pt: &Point3<f32>) -> Vector3<f32> {
         self.snorm
     }
+
+    // The inside of a plane is the half-space behind its surface normal
+    fn intersection_intervals(&self, pos: &Point3<f32>, ray: &Ray) -> Vec<(f32, f32)> {
+        let height = (ray.pos() - pos).dot(&self.snorm);
+        let rate = ray.dir().dot(&self.snorm);
+        if rate == 0.0 {
+            return if height < 0.0 { vec![(f32::NEG_INFINITY, f32::INFINITY)] } else { vec![] }
+        }
+        let t = -height / rate;
+        if rate > 0.0 {
+            vec![(f32::NEG_INFINITY, t)]
+        } else {
+            vec![(t, f32::INFINITY)]
+        }
+    }
 }
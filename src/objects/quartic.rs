@@ -0,0 +1,143 @@
+// Real root finding for polynomials up to degree four. Computations are done in f64 since the
+// closed forms lose most of their precision in f32, and every root is polished with a few
+// Newton iterations against the original polynomial.
+
+const EPS: f64 = 1e-12;
+const NEWTON_ITERATIONS: usize = 4;
+
+// Returns the real roots of a*x^2 + b*x + c in ascending order
+pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a.abs() < EPS {
+        if b.abs() < EPS {
+            return vec![]
+        }
+        return vec![-c / b]
+    }
+    let d = b * b - 4.0 * a * c;
+    if d < 0.0 {
+        return vec![]
+    }
+    // Avoids cancellation between -b and sqrt(d)
+    let q = -0.5 * (b + b.signum() * d.sqrt());
+    let (r1, r2) = if q.abs() < EPS { (0.0, 0.0) } else { (q / a, c / q) };
+    if r1 < r2 { vec![r1, r2] } else { vec![r2, r1] }
+}
+
+// Returns the largest real root of x^3 + a*x^2 + b*x + c
+fn largest_cubic_root(a: f64, b: f64, c: f64) -> f64 {
+    let p = b - a * a / 3.0;
+    let q = (2.0 * a.powi(3) / 27.0) - (a * b / 3.0) + c;
+    let d = (q / 2.0).powi(2) + (p / 3.0).powi(3);
+    let t = if d > 0.0 {
+        (-q / 2.0 + d.sqrt()).cbrt() + (-q / 2.0 - d.sqrt()).cbrt()
+    } else if p.abs() < EPS {
+        (-q).cbrt()
+    } else {
+        let angle = ((3.0 * q / (2.0 * p)) * (-3.0 / p).sqrt()).clamp(-1.0, 1.0).acos() / 3.0;
+        2.0 * (-p / 3.0).sqrt() * angle.cos()
+    };
+    let mut x = t - a / 3.0;
+    for _ in 0..NEWTON_ITERATIONS {
+        let f = ((x + a) * x + b) * x + c;
+        let df = (3.0 * x + 2.0 * a) * x + b;
+        if df.abs() < EPS {
+            break;
+        }
+        x -= f / df;
+    }
+    x
+}
+
+// Returns the real roots of c[0]*x^4 + c[1]*x^3 + c[2]*x^2 + c[3]*x + c[4] in ascending order
+pub fn solve_quartic(coeffs: [f64; 5]) -> Vec<f64> {
+    if coeffs[0].abs() < EPS {
+        // Degenerate quartics only show up for rays that are (numerically) zero length
+        return solve_quadratic(coeffs[2], coeffs[3], coeffs[4]);
+    }
+    let a = coeffs[1] / coeffs[0];
+    let b = coeffs[2] / coeffs[0];
+    let c = coeffs[3] / coeffs[0];
+    let d = coeffs[4] / coeffs[0];
+
+    // Depressed quartic y^4 + p*y^2 + q*y + r with x = y - a/4
+    let p = b - (3.0 * a * a / 8.0);
+    let q = c - (a * b / 2.0) + (a.powi(3) / 8.0);
+    let r = d - (a * c / 4.0) + (a * a * b / 16.0) - (3.0 * a.powi(4) / 256.0);
+
+    let mut roots: Vec<f64> = vec![];
+    if q.abs() < EPS {
+        // Biquadratic, solve for y^2
+        for z in solve_quadratic(1.0, p, r) {
+            if z >= 0.0 {
+                roots.push(z.sqrt());
+                roots.push(-z.sqrt());
+            }
+        }
+    } else {
+        // Ferrari's method, m is a positive root of the resolvent cubic
+        let m = largest_cubic_root(p, (p * p / 4.0) - r, -q * q / 8.0);
+        if m <= 0.0 {
+            return vec![]
+        }
+        let s = (2.0 * m).sqrt();
+        roots.extend(solve_quadratic(1.0, -s, (p / 2.0) + m + (q / (2.0 * s))));
+        roots.extend(solve_quadratic(1.0, s, (p / 2.0) + m - (q / (2.0 * s))));
+    }
+
+    let mut roots: Vec<f64> = roots.iter().map(|y| polish(&coeffs, y - a / 4.0)).collect();
+    roots.sort_by(|x, y| x.total_cmp(y));
+    roots
+}
+
+fn polish(coeffs: &[f64; 5], mut x: f64) -> f64 {
+    for _ in 0..NEWTON_ITERATIONS {
+        let f = coeffs.iter().fold(0.0, |acc, c| (acc * x) + c);
+        let df = (((4.0 * coeffs[0] * x) + (3.0 * coeffs[1])) * x + (2.0 * coeffs[2])) * x + coeffs[3];
+        if df.abs() < EPS {
+            break;
+        }
+        x -= f / df;
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_roots(roots: Vec<f64>, expected: &[f64]) {
+        assert_eq!(roots.len(), expected.len(), "Roots were {:?}", roots);
+        for (root, expected) in roots.iter().zip(expected) {
+            assert!((root - expected).abs() < 1e-6, "Roots were {:?}, expected {:?}", roots, expected);
+        }
+    }
+
+    #[test]
+    fn quadratic_roots() {
+        assert_roots(solve_quadratic(1.0, -3.0, 2.0), &[1.0, 2.0]);
+        assert_roots(solve_quadratic(0.0, 2.0, -4.0), &[2.0]);
+        assert_roots(solve_quadratic(1.0, 0.0, 1.0), &[]);
+    }
+
+    #[test]
+    fn quartic_with_four_roots() {
+        // (x - 1)(x - 2)(x - 3)(x - 4)
+        assert_roots(solve_quartic([1.0, -10.0, 35.0, -50.0, 24.0]), &[1.0, 2.0, 3.0, 4.0]);
+        // 2(x + 3)(x + 0.5)(x - 0.25)(x - 5), scaled and not centered on 0
+        assert_roots(solve_quartic([2.0, -3.5, -31.25, -7.0, 3.75]), &[-3.0, -0.5, 0.25, 5.0]);
+    }
+
+    #[test]
+    fn biquadratic_quartic() {
+        // (x^2 - 1)(x^2 - 4)
+        assert_roots(solve_quartic([1.0, 0.0, -5.0, 0.0, 4.0]), &[-2.0, -1.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn quartic_with_two_or_no_real_roots() {
+        // (x^2 - 1)(x^2 + 1)
+        assert_roots(solve_quartic([1.0, 0.0, 0.0, 0.0, -1.0]), &[-1.0, 1.0]);
+        // (x^2 + 1)(x^2 + 2x + 5)
+        assert_roots(solve_quartic([1.0, 2.0, 6.0, 2.0, 5.0]), &[]);
+    }
+}
@@ -0,0 +1,150 @@
+use nalgebra::{Vector3, Point3};
+use crate::objects::Object;
+use crate::ray::Ray;
+
+pub mod shapes;
+pub mod operations;
+
+// Signed distance to a surface, negative inside. Any closure from a point to a distance can be used,
+// along with the shapes and operations in this module.
+pub trait DistanceFunction {
+    fn distance(&self, p: &Vector3<f32>) -> f32;
+}
+
+impl<F: Fn(&Vector3<f32>) -> f32> DistanceFunction for F {
+    fn distance(&self, p: &Vector3<f32>) -> f32 {
+        self(p)
+    }
+}
+
+const GRADIENT_STEP: f32 = 0.0001;
+
+// Object defined by a distance function and intersected by sphere tracing
+pub struct Sdf {
+    function: Box<dyn DistanceFunction>,
+    max_steps: u32,
+    max_distance: f32, // Radius around the object's position that the whole surface lies within
+    epsilon: f32,
+    step_scale: f32, // Fraction of the distance to step, below 1 for functions that overestimate (e.g. twists)
+}
+
+impl Sdf {
+    pub fn new(function: Box<dyn DistanceFunction>) -> Self {
+        Self::with_limits(function, 512, 100.0, 0.0001, 1.0)
+    }
+
+    pub fn with_limits(function: Box<dyn DistanceFunction>, max_steps: u32, max_distance: f32, epsilon: f32, step_scale: f32) -> Self {
+        if step_scale <= 0.0 || 1.0 < step_scale {
+            panic!("Step scale must be a number in (0, 1], given step scale was {}", step_scale)
+        }
+        Self {
+            function,
+            max_steps,
+            max_distance,
+            epsilon,
+            step_scale,
+        }
+    }
+
+    fn distance(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> f32 {
+        self.function.distance(&(pt - pos))
+    }
+
+    // Range of t where the ray is within max_distance of the object's position, if it gets that close
+    fn bounds(&self, pos: &Point3<f32>, ray: &Ray) -> Option<(f32, f32)> {
+        let to_pos = pos - ray.pos();
+        let along = to_pos.dot(&ray.dir());
+        let half_squared = self.max_distance.powi(2) - (to_pos.magnitude_squared() - along.powi(2));
+        if half_squared < 0.0 {
+            return None
+        }
+        let half = half_squared.sqrt();
+        Some((along - half, along + half))
+    }
+
+    // Marches along the ray from start, returning the t of the first surface crossing
+    fn march(&self, pos: &Point3<f32>, ray: &Ray, start: f32) -> Option<f32> {
+        let (enter, exit) = self.bounds(pos, ray)?;
+        let mut t = start.max(enter);
+        for _ in 0..self.max_steps {
+            let d = self.distance(pos, &(ray.pos() + (t * ray.dir()))).abs();
+            if d < self.epsilon {
+                return Some(t)
+            }
+            t += d * self.step_scale;
+            if t > exit {
+                break;
+            }
+        }
+        None
+    }
+}
+
+impl Object for Sdf {
+    fn intersection_point(&self, pos: &Point3<f32>, ray: &Ray, tolerance: f32) -> Option<Point3<f32>> {
+        self.march(pos, ray, tolerance).map(|t| ray.at(t))
+    }
+
+    // Gradient of the distance function from central differences on a tetrahedron
+    fn surface_normal(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> Vector3<f32> {
+        let h = GRADIENT_STEP;
+        let offsets = [
+            Vector3::new(1.0, -1.0, -1.0),
+            Vector3::new(-1.0, -1.0, 1.0),
+            Vector3::new(-1.0, 1.0, -1.0),
+            Vector3::new(1.0, 1.0, 1.0),
+        ];
+        let gradient: Vector3<f32> = offsets.iter().map(|k| k * self.distance(pos, &(pt + (h * k)))).sum();
+        if gradient.magnitude() == 0.0 {
+            return Vector3::new(0.0, 1.0, 0.0)
+        }
+        gradient.normalize()
+    }
+
+    // Marches over the part of the line within the maximum distance, splitting at each crossing. Being inside
+    // where that part starts or ends means the inside carries on past it.
+    fn intersection_intervals(&self, pos: &Point3<f32>, ray: &Ray) -> Vec<(f32, f32)> {
+        let mut intervals: Vec<(f32, f32)> = vec![];
+        let Some((mut t, _)) = self.bounds(pos, ray) else {
+            let inside = self.distance(pos, &ray.pos()) < 0.0;
+            return if inside { vec![(f32::NEG_INFINITY, f32::INFINITY)] } else { vec![] }
+        };
+        let mut start: Option<f32> = if self.distance(pos, &(ray.pos() + (t * ray.dir()))) < 0.0 { Some(f32::NEG_INFINITY) } else { None };
+        for _ in 0..self.max_steps {
+            let Some(crossing) = self.march(pos, ray, t) else { break };
+            // Step over the surface to find which side the ray continues on
+            t = crossing + (2.0 * self.epsilon);
+            let inside = self.distance(pos, &(ray.pos() + (t * ray.dir()))) < 0.0;
+            match start {
+                None if inside => start = Some(crossing),
+                Some(enter) if !inside => {
+                    intervals.push((enter, crossing));
+                    start = None;
+                },
+                _ => { },
+            }
+        }
+        if let Some(enter) = start {
+            intervals.push((enter, f32::INFINITY));
+        }
+        intervals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::sdf::shapes::Sphere;
+
+    #[test]
+    fn finds_surfaces_far_from_the_ray_origin() {
+        let sdf = Sdf::new(Box::new(Sphere::new(1.0)));
+        let pos = Point3::new(0.0, 0.0, -500.0);
+        let ray = Ray::new(Point3::origin(), Vector3::new(0.0, 0.0, -1.0));
+        let hit = sdf.intersection_point(&pos, &ray, 0.0).expect("Ray should hit the sphere");
+        assert!((hit.z + 499.0).abs() < 0.001);
+        let intervals = sdf.intersection_intervals(&pos, &ray);
+        assert_eq!(intervals.len(), 1);
+        assert!((intervals[0].0 - 499.0).abs() < 0.001 && (intervals[0].1 - 501.0).abs() < 0.001);
+    }
+}
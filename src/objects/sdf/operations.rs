@@ -0,0 +1,224 @@
+use nalgebra::{Vector3, UnitQuaternion};
+use crate::objects::sdf::DistanceFunction;
+
+// Polynomial smooth minimum, k is the size of the blended region
+pub fn smooth_min(a: f32, b: f32, k: f32) -> f32 {
+    if k <= 0.0 {
+        return a.min(b)
+    }
+    let h = (0.5 + (0.5 * (b - a) / k)).clamp(0.0, 1.0);
+    (b * (1.0 - h)) + (a * h) - (k * h * (1.0 - h))
+}
+
+pub fn smooth_max(a: f32, b: f32, k: f32) -> f32 {
+    -smooth_min(-a, -b, k)
+}
+
+pub struct Union {
+    a: Box<dyn DistanceFunction>,
+    b: Box<dyn DistanceFunction>,
+    smoothness: f32,
+}
+
+impl Union {
+    pub fn new(a: Box<dyn DistanceFunction>, b: Box<dyn DistanceFunction>) -> Self {
+        Self::smooth(a, b, 0.0)
+    }
+
+    pub fn smooth(a: Box<dyn DistanceFunction>, b: Box<dyn DistanceFunction>, smoothness: f32) -> Self {
+        Self {
+            a,
+            b,
+            smoothness,
+        }
+    }
+}
+
+impl DistanceFunction for Union {
+    fn distance(&self, p: &Vector3<f32>) -> f32 {
+        smooth_min(self.a.distance(p), self.b.distance(p), self.smoothness)
+    }
+}
+
+pub struct Intersection {
+    a: Box<dyn DistanceFunction>,
+    b: Box<dyn DistanceFunction>,
+    smoothness: f32,
+}
+
+impl Intersection {
+    pub fn new(a: Box<dyn DistanceFunction>, b: Box<dyn DistanceFunction>) -> Self {
+        Self::smooth(a, b, 0.0)
+    }
+
+    pub fn smooth(a: Box<dyn DistanceFunction>, b: Box<dyn DistanceFunction>, smoothness: f32) -> Self {
+        Self {
+            a,
+            b,
+            smoothness,
+        }
+    }
+}
+
+impl DistanceFunction for Intersection {
+    fn distance(&self, p: &Vector3<f32>) -> f32 {
+        smooth_max(self.a.distance(p), self.b.distance(p), self.smoothness)
+    }
+}
+
+pub struct Difference { // a minus b
+    a: Box<dyn DistanceFunction>,
+    b: Box<dyn DistanceFunction>,
+    smoothness: f32,
+}
+
+impl Difference {
+    pub fn new(a: Box<dyn DistanceFunction>, b: Box<dyn DistanceFunction>) -> Self {
+        Self::smooth(a, b, 0.0)
+    }
+
+    pub fn smooth(a: Box<dyn DistanceFunction>, b: Box<dyn DistanceFunction>, smoothness: f32) -> Self {
+        Self {
+            a,
+            b,
+            smoothness,
+        }
+    }
+}
+
+impl DistanceFunction for Difference {
+    fn distance(&self, p: &Vector3<f32>) -> f32 {
+        smooth_max(self.a.distance(p), -self.b.distance(p), self.smoothness)
+    }
+}
+
+// Linear interpolation between two shapes, amount 0 gives a and 1 gives b
+pub struct Blend {
+    a: Box<dyn DistanceFunction>,
+    b: Box<dyn DistanceFunction>,
+    amount: f32,
+}
+
+impl Blend {
+    pub fn new(a: Box<dyn DistanceFunction>, b: Box<dyn DistanceFunction>, amount: f32) -> Self {
+        Self {
+            a,
+            b,
+            amount,
+        }
+    }
+}
+
+impl DistanceFunction for Blend {
+    fn distance(&self, p: &Vector3<f32>) -> f32 {
+        ((1.0 - self.amount) * self.a.distance(p)) + (self.amount * self.b.distance(p))
+    }
+}
+
+pub struct Translate {
+    shape: Box<dyn DistanceFunction>,
+    offset: Vector3<f32>,
+}
+
+impl Translate {
+    pub fn new(shape: Box<dyn DistanceFunction>, offset: Vector3<f32>) -> Self {
+        Self {
+            shape,
+            offset,
+        }
+    }
+}
+
+impl DistanceFunction for Translate {
+    fn distance(&self, p: &Vector3<f32>) -> f32 {
+        self.shape.distance(&(p - self.offset))
+    }
+}
+
+pub struct Rotate {
+    shape: Box<dyn DistanceFunction>,
+    rotation: UnitQuaternion<f32>,
+}
+
+impl Rotate {
+    pub fn new(shape: Box<dyn DistanceFunction>, rotation: UnitQuaternion<f32>) -> Self {
+        Self {
+            shape,
+            rotation,
+        }
+    }
+}
+
+impl DistanceFunction for Rotate {
+    fn distance(&self, p: &Vector3<f32>) -> f32 {
+        self.shape.distance(&(self.rotation.inverse() * p))
+    }
+}
+
+pub struct Scale {
+    shape: Box<dyn DistanceFunction>,
+    factor: f32,
+}
+
+impl Scale {
+    pub fn new(shape: Box<dyn DistanceFunction>, factor: f32) -> Self {
+        if factor <= 0.0 {
+            panic!("Scale factor must be positive, given factor was {}", factor)
+        }
+        Self {
+            shape,
+            factor,
+        }
+    }
+}
+
+impl DistanceFunction for Scale {
+    fn distance(&self, p: &Vector3<f32>) -> f32 {
+        self.shape.distance(&(p / self.factor)) * self.factor
+    }
+}
+
+// Twists a shape around the y axis. Twisting stretches distances, so objects using it should
+// be traced with a step scale below 1.
+pub struct Twist {
+    shape: Box<dyn DistanceFunction>,
+    rate: f32, // Radians per unit along y
+}
+
+impl Twist {
+    pub fn new(shape: Box<dyn DistanceFunction>, rate: f32) -> Self {
+        Self {
+            shape,
+            rate,
+        }
+    }
+}
+
+impl DistanceFunction for Twist {
+    fn distance(&self, p: &Vector3<f32>) -> f32 {
+        let (s, c) = (self.rate * p.y).sin_cos();
+        self.shape.distance(&Vector3::new((c * p.x) - (s * p.z), p.y, (s * p.x) + (c * p.z)))
+    }
+}
+
+// Repeats a shape infinitely on a grid. A period of 0 along an axis disables repetition along it.
+pub struct Repeat {
+    shape: Box<dyn DistanceFunction>,
+    period: Vector3<f32>,
+}
+
+impl Repeat {
+    pub fn new(shape: Box<dyn DistanceFunction>, period: Vector3<f32>) -> Self {
+        Self {
+            shape,
+            period,
+        }
+    }
+}
+
+impl DistanceFunction for Repeat {
+    fn distance(&self, p: &Vector3<f32>) -> f32 {
+        let q = p.zip_map(&self.period, |v, period| if period > 0.0 { v - (period * (v / period).round()) } else { v });
+        self.shape.distance(&q)
+    }
+}
@@ -0,0 +1,174 @@
+use nalgebra::Vector3;
+use std::f32::consts::LN_2;
+use crate::objects::sdf::DistanceFunction;
+
+pub struct Sphere {
+    radius: f32,
+}
+
+impl Sphere {
+    pub fn new(radius: f32) -> Self {
+        Self {
+            radius,
+        }
+    }
+}
+
+impl DistanceFunction for Sphere {
+    fn distance(&self, p: &Vector3<f32>) -> f32 {
+        p.magnitude() - self.radius
+    }
+}
+
+pub struct Cuboid {
+    half_extents: Vector3<f32>,
+    rounding: f32,
+}
+
+impl Cuboid {
+    pub fn new(half_extents: Vector3<f32>) -> Self {
+        Self::rounded(half_extents, 0.0)
+    }
+
+    pub fn rounded(half_extents: Vector3<f32>, rounding: f32) -> Self {
+        Self {
+            half_extents,
+            rounding,
+        }
+    }
+}
+
+impl DistanceFunction for Cuboid {
+    fn distance(&self, p: &Vector3<f32>) -> f32 {
+        let q = p.abs() - self.half_extents + Vector3::repeat(self.rounding);
+        q.map(|v| v.max(0.0)).magnitude() + q.max().min(0.0) - self.rounding
+    }
+}
+
+pub struct Torus { // Ring lies in the xz-plane
+    major_radius: f32,
+    minor_radius: f32,
+}
+
+impl Torus {
+    pub fn new(major_radius: f32, minor_radius: f32) -> Self {
+        Self {
+            major_radius,
+            minor_radius,
+        }
+    }
+}
+
+impl DistanceFunction for Torus {
+    fn distance(&self, p: &Vector3<f32>) -> f32 {
+        let ring = Vector3::new(p.x, 0.0, p.z).magnitude() - self.major_radius;
+        (ring.powi(2) + p.y.powi(2)).sqrt() - self.minor_radius
+    }
+}
+
+pub struct Capsule {
+    a: Vector3<f32>,
+    b: Vector3<f32>,
+    radius: f32,
+}
+
+impl Capsule {
+    pub fn new(a: Vector3<f32>, b: Vector3<f32>, radius: f32) -> Self {
+        Self {
+            a,
+            b,
+            radius,
+        }
+    }
+}
+
+impl DistanceFunction for Capsule {
+    fn distance(&self, p: &Vector3<f32>) -> f32 {
+        let pa = p - self.a;
+        let ba = self.b - self.a;
+        let h = (pa.dot(&ba) / ba.dot(&ba)).clamp(0.0, 1.0);
+        (pa - (h * ba)).magnitude() - self.radius
+    }
+}
+
+pub struct Cylinder { // Capped, axis along y
+    radius: f32,
+    height: f32,
+}
+
+impl Cylinder {
+    pub fn new(radius: f32, height: f32) -> Self {
+        Self {
+            radius,
+            height,
+        }
+    }
+}
+
+impl DistanceFunction for Cylinder {
+    fn distance(&self, p: &Vector3<f32>) -> f32 {
+        let dx = Vector3::new(p.x, 0.0, p.z).magnitude() - self.radius;
+        let dy = p.y.abs() - (self.height / 2.0);
+        dx.max(dy).min(0.0) + (dx.max(0.0).powi(2) + dy.max(0.0).powi(2)).sqrt()
+    }
+}
+
+pub struct Plane {
+    snorm: Vector3<f32>,
+}
+
+impl Plane {
+    pub fn new(snorm: Vector3<f32>) -> Self {
+        Self {
+            snorm: snorm.normalize(),
+        }
+    }
+}
+
+impl DistanceFunction for Plane {
+    fn distance(&self, p: &Vector3<f32>) -> f32 {
+        p.dot(&self.snorm)
+    }
+}
+
+// Distance estimate for the power-n Mandelbulb fractal
+pub struct Mandelbulb {
+    power: f32,
+    iterations: u32,
+}
+
+impl Mandelbulb {
+    pub fn new(power: f32, iterations: u32) -> Self {
+        Self {
+            power,
+            iterations,
+        }
+    }
+}
+
+impl DistanceFunction for Mandelbulb {
+    fn distance(&self, p: &Vector3<f32>) -> f32 {
+        // The fractal lies within the escape radius, and the estimate below overshoots far outside it
+        if p.magnitude() > 2.0 {
+            return p.magnitude() - 2.0 + LN_2
+        }
+        let mut z = *p;
+        let mut dr: f32 = 1.0;
+        let mut r: f32 = 0.0;
+        for _ in 0..self.iterations {
+            r = z.magnitude();
+            if r > 2.0 {
+                break;
+            }
+            let theta = (z.z / r).clamp(-1.0, 1.0).acos() * self.power;
+            let phi = z.y.atan2(z.x) * self.power;
+            dr = (r.powf(self.power - 1.0) * self.power * dr) + 1.0;
+            let zr = r.powf(self.power);
+            z = (zr * Vector3::new(theta.sin() * phi.cos(), theta.sin() * phi.sin(), theta.cos())) + p;
+        }
+        if r == 0.0 {
+            return -1.0
+        }
+        0.5 * r.ln() * r / dr
+    }
+}
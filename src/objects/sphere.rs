@@ -1,4 +1,5 @@
 use nalgebra::{Vector3, Point3};
+use std::f32::consts::PI;
 use crate::objects::Object;
 use crate::ray::Ray;
 
@@ -21,9 +22,13 @@ impl Object for Sphere {
         let c = (ray.pos() - center).dot(&(ray.pos() - center)) - self.radius.powi(2);
         let d = b.powi(2) - (4.0 * a * c);
         if d >= 0.0 {
-            let t = ((-b + d.sqrt()) / (2.0 * a)).min((-b - d.sqrt()) / (2.0 * a));
-            if t > tolerance {
-                return Some(ray.at(t))
+            // The far side is hit when the ray starts inside the sphere
+            let (near, far) = ((-b - d.sqrt()) / (2.0 * a), (-b + d.sqrt()) / (2.0 * a));
+            if near > tolerance {
+                return Some(ray.at(near))
+            }
+            if far > tolerance {
+                return Some(ray.at(far))
             }
         }
         None
@@ -32,4 +37,29 @@ impl Object for Sphere {
     fn surface_normal(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> Vector3<f32> {
         (pt - pos).normalize() 
     }
+
+    fn intersection_intervals(&self, center: &Point3<f32>, ray: &Ray) -> Vec<(f32, f32)> {
+        let b = ray.dir().dot(&(ray.pos() - center));
+        let c = (ray.pos() - center).dot(&(ray.pos() - center)) - self.radius.powi(2);
+        let d = b.powi(2) - (ray.dir().dot(&ray.dir()) * c);
+        if d < 0.0 {
+            return vec![]
+        }
+        let a = ray.dir().dot(&ray.dir());
+        vec![((-b - d.sqrt()) / a, (-b + d.sqrt()) / a)]
+    }
+
+    // Points are spread evenly over the whole surface
+    fn sample_surface(&self, center: &Point3<f32>, (u1, u2): (f32, f32)) -> Option<(Point3<f32>, Vector3<f32>, f32)> {
+        let z = 1.0 - (2.0 * u1);
+        let r = (1.0 - (z * z)).max(0.0).sqrt();
+        let phi = 2.0 * PI * u2;
+        let normal = Vector3::new(r * phi.cos(), r * phi.sin(), z);
+        let point = center + (self.radius * normal);
+        Some((point, normal, self.surface_pdf(center, &point)))
+    }
+
+    fn surface_pdf(&self, _center: &Point3<f32>, _pt: &Point3<f32>) -> f32 {
+        1.0 / (4.0 * PI * self.radius.powi(2))
+    }
 }
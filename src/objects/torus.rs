@@ -0,0 +1,113 @@
+use nalgebra::{Vector3, Point3, UnitQuaternion};
+use std::f32::consts::PI;
+use crate::objects::Object;
+use crate::objects::quartic::solve_quartic;
+use crate::ray::Ray;
+
+pub struct Torus {
+    major_radius: f32,
+    minor_radius: f32,
+    rotation: UnitQuaternion<f32>, // Rotates from torus space (axis along +y) to world space
+}
+
+impl Torus {
+    pub fn new(major_radius: f32, minor_radius: f32) -> Self {
+        Self::with_axis(major_radius, minor_radius, Vector3::new(0.0, 1.0, 0.0))
+    }
+
+    pub fn with_axis(major_radius: f32, minor_radius: f32, axis: Vector3<f32>) -> Self {
+        if major_radius <= 0.0 || minor_radius <= 0.0 {
+            panic!("Torus radii must be positive, given radii were {} and {}", major_radius, minor_radius)
+        }
+        let rotation = UnitQuaternion::rotation_between(&Vector3::new(0.0, 1.0, 0.0), &axis.normalize())
+            .unwrap_or_else(|| UnitQuaternion::from_axis_angle(&Vector3::x_axis(), PI));
+        Self {
+            major_radius,
+            minor_radius,
+            rotation,
+        }
+    }
+
+    fn to_local(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> Vector3<f32> {
+        self.rotation.inverse() * (pt - pos)
+    }
+
+    // Returns every ray parameter t where the ray crosses the surface, in ascending order
+    fn crossings(&self, pos: &Point3<f32>, ray: &Ray) -> Vec<f32> {
+        let o = self.to_local(pos, &ray.pos());
+        let d = self.rotation.inverse() * ray.dir();
+
+        // Start the ray at the bounding sphere so far away origins don't drown the roots
+        let bound = self.major_radius + self.minor_radius;
+        let b = o.dot(&d);
+        let disc = b.powi(2) - (o.dot(&o) - bound.powi(2));
+        if disc < 0.0 {
+            return vec![]
+        }
+        let t0 = (-b - disc.sqrt()).max(0.0) as f64;
+
+        let o = o.cast::<f64>() + (t0 * d.cast::<f64>());
+        let d = d.cast::<f64>();
+        let rr = (self.major_radius as f64).powi(2);
+        let b = 2.0 * o.dot(&d);
+        let c = o.dot(&o) + rr - (self.minor_radius as f64).powi(2);
+        let coeffs = [
+            1.0,
+            2.0 * b,
+            (b * b) + (2.0 * c) - (4.0 * rr * (d.x.powi(2) + d.z.powi(2))),
+            (2.0 * b * c) - (8.0 * rr * ((o.x * d.x) + (o.z * d.z))),
+            (c * c) - (4.0 * rr * (o.x.powi(2) + o.z.powi(2))),
+        ];
+        solve_quartic(coeffs).iter().map(|t| (t + t0) as f32).collect()
+    }
+}
+
+impl Object for Torus {
+    fn intersection_point(&self, pos: &Point3<f32>, ray: &Ray, tolerance: f32) -> Option<Point3<f32>> {
+        self.crossings(pos, ray).into_iter().find(|t| *t > tolerance).map(|t| ray.at(t))
+    }
+
+    fn surface_normal(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> Vector3<f32> {
+        let p = self.to_local(pos, pt);
+        let ring = Vector3::new(p.x, 0.0, p.z);
+        let ring = if ring.magnitude() > 0.0 { ring.normalize() } else { Vector3::new(1.0, 0.0, 0.0) };
+        (self.rotation * (p - (self.major_radius * ring))).normalize()
+    }
+
+    fn intersection_intervals(&self, pos: &Point3<f32>, ray: &Ray) -> Vec<(f32, f32)> {
+        self.crossings(pos, ray).chunks_exact(2).map(|pair| (pair[0], pair[1])).collect()
+    }
+
+    fn surface_uv(&self, pos: &Point3<f32>, pt: &Point3<f32>) -> Option<(f32, f32)> {
+        let p = self.to_local(pos, pt);
+        let u = 0.5 + (p.z.atan2(p.x) / (2.0 * PI));
+        let v = 0.5 + (p.y.atan2(Vector3::new(p.x, 0.0, p.z).magnitude() - self.major_radius) / (2.0 * PI));
+        Some((u, v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ray_through_the_hole_crosses_both_sides_of_the_ring() {
+        let torus = Torus::new(2.0, 0.5);
+        let ray = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        let intervals = torus.intersection_intervals(&Point3::origin(), &ray);
+        let expected = [(2.5, 3.5), (6.5, 7.5)];
+        assert_eq!(intervals.len(), expected.len(), "Intervals were {:?}", intervals);
+        for ((enter, exit), (expected_enter, expected_exit)) in intervals.iter().zip(expected) {
+            assert!((enter - expected_enter).abs() < 0.001 && (exit - expected_exit).abs() < 0.001, "Intervals were {:?}", intervals);
+        }
+        let hit = torus.intersection_point(&Point3::origin(), &ray, 0.001).expect("Ray should hit the torus");
+        assert!((hit - Point3::new(-2.5, 0.0, 0.0)).magnitude() < 0.001);
+    }
+
+    #[test]
+    fn ray_along_the_axis_misses() {
+        let torus = Torus::with_axis(2.0, 0.5, Vector3::new(0.0, 0.0, 1.0));
+        let ray = Ray::new(Point3::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, -1.0));
+        assert!(torus.intersection_point(&Point3::origin(), &ray, 0.001).is_none());
+    }
+}
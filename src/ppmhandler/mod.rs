@@ -46,7 +46,7 @@ impl PPMImage {
         }
     }
 
-    pub fn change_pixel(&mut self, x: usize, y: usize, color: Vector3<u32>) -> () {
+    pub fn change_pixel(&mut self, x: usize, y: usize, color: Vector3<u32>) {
         if x >= self.dim.0 || y >= self.dim.1 {
             panic!("Pixel ({}, {}) does not exist!", x, y);
         } else if color.x > self.maxval || color.y > self.maxval || color.z > self.maxval {
@@ -66,7 +66,7 @@ impl PPMImage {
         self.maxval
     }
 
-    pub fn gamma_correct(&mut self) -> () {
+    pub fn gamma_correct(&mut self) {
         self.data = self.data.iter().map(|row| row.iter().map(|pix| Vector3::<u32>::new(
             (pix.x as f32).sqrt() as u32, 
             (pix.y as f32).sqrt() as u32, 
@@ -99,31 +99,30 @@ impl PPMHandler {
         let mut g: Option<u32> = None;
         let mut b: Option<u32> = None;
 
-        for line_result in lines {
-            if let Ok(line) = line_result {
-                if line.starts_with("#") {
-                    continue;
-                } else {
-                    self.read_line(
-                        &line, 
-                        &mut ppm_type, 
-                        &mut width, 
-                        &mut height, 
-                        &mut maxval, 
-                        &mut image,
-                        &mut x,
-                        &mut y,
-                        &mut count,
-                        &mut r,
-                        &mut g,
-                        &mut b);
-                }
+        for line in lines.map_while(Result::ok) {
+            if line.starts_with('#') {
+                continue;
+            } else {
+                self.read_line(
+                    &line, 
+                    &mut ppm_type, 
+                    &mut width, 
+                    &mut height, 
+                    &mut maxval, 
+                    &mut image,
+                    &mut x,
+                    &mut y,
+                    &mut count,
+                    &mut r,
+                    &mut g,
+                    &mut b);
             }
         }
 
         Ok(image)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn read_line(&self, 
         line: &str, 
         ppm_type: &mut Option<PPMType>,
@@ -136,7 +135,7 @@ impl PPMHandler {
         count: &mut usize,
         r: &mut Option<u32>,
         g: &mut Option<u32>,
-        b: &mut Option<u32>) -> () {
+        b: &mut Option<u32>) {
         for token in line.split_whitespace() {
             if ppm_type.is_none() {
                 if token == "P3" {
@@ -147,19 +146,19 @@ impl PPMHandler {
                     panic!("Error when reading .ppm file: ppm type can not be read");
                 }
             } else if width.is_none() {
-                if token.parse::<usize>().map_or(false, |_w| true) {
+                if token.parse::<usize>().is_ok() {
                     *width = Some(token.parse::<usize>().unwrap());
                 } else {
                     panic!("Error when reading .ppm file: width can not be read");
                 }
             } else if height.is_none() {
-                if token.parse::<usize>().map_or(false, |_h| true) {
+                if token.parse::<usize>().is_ok() {
                     *height = Some(token.parse::<usize>().unwrap());
                 } else {
                     panic!("Error when reading .ppm file: height can not be read");
                 }
             } else if maxval.is_none() {
-                if token.parse::<u16>().map_or(false, |_m| true) {
+                if token.parse::<u16>().is_ok() {
                     *maxval = Some(token.parse::<u32>().unwrap());
                     *image = PPMImage::new(width.unwrap(), height.unwrap(), maxval.unwrap());
                 } else {
@@ -167,19 +166,19 @@ impl PPMHandler {
                 }
             } else {
                 if r.is_none() {
-                    if token.parse::<u16>().map_or(false, |_r| true) {
+                    if token.parse::<u16>().is_ok() {
                         *r = Some(token.parse::<u32>().unwrap());
                     } else {
                         panic!("Error when reading .ppm file: r value can not be read");
                     }
                 } else if g.is_none() {
-                    if token.parse::<u16>().map_or(false, |_g| true) {
+                    if token.parse::<u16>().is_ok() {
                         *g = Some(token.parse::<u32>().unwrap());
                     } else {
                         panic!("Error when reading .ppm file: g value can not be read");
                     }
                 } else if b.is_none() {
-                    if token.parse::<u16>().map_or(false, |_g| true) {
+                    if token.parse::<u16>().is_ok() {
                         *b = Some(token.parse::<u32>().unwrap());
                         // println!("{}, ({}, {})", *count, *x, *y);
                         image.change_pixel(
@@ -211,27 +210,27 @@ impl PPMHandler {
         let mut writer = BufWriter::new(File::create(path.to_owned() + ".ppm")?);
         match ppm_type {
             PPMType::P3 => {
-                writer.write(b"P3 \n").expect("Error when writing .ppm file: writing magic number failed");
-                writer.write((
+                writer.write_all(b"P3 \n").expect("Error when writing .ppm file: writing magic number failed");
+                writer.write_all((
                     image.dim().0.to_string() + 
                     " " + 
                     &image.dim().1.to_string() + 
                     " \n")
                     .as_bytes()).expect("Error when writing .ppm file: writing dimensions failed");
-                writer.write((image.maxval().to_string() + " \n").as_bytes())
+                writer.write_all((image.maxval().to_string() + " \n").as_bytes())
                     .expect("Error when writing .ppm file: writing max value failed");
 
                 for y in 0..image.dim().1 {
                     for x in 0..image.dim().0 {
-                        writer.write((" ".to_owned() + 
+                        writer.write_all((" ".to_owned() + 
                             &image.pixel_at(x, y).x.to_string() + 
                             " \n").as_bytes())
                             .expect("Error when writing .ppm file: writing color value failed");
-                        writer.write((" ".to_owned() + 
+                        writer.write_all((" ".to_owned() + 
                             &image.pixel_at(x, y).y.to_string() + 
                             " \n").as_bytes())
                             .expect("Error when writing .ppm file: writing color value failed");
-                        writer.write((" ".to_owned() + 
+                        writer.write_all((" ".to_owned() + 
                             &image.pixel_at(x, y).z.to_string() + 
                             " \n").as_bytes())
                             .expect("Error when writing .ppm file: writing color value failed");
@@ -239,7 +238,7 @@ impl PPMHandler {
                 }
             },
             PPMType::P6 => {
-                writer.write(("P6 ".to_owned() + 
+                writer.write_all(("P6 ".to_owned() + 
                     &image.dim().0.to_string() + " " +
                     &image.dim().1.to_string() + " " +
                     &image.maxval().to_string() + "\n").as_bytes())
@@ -4,13 +4,26 @@ use nalgebra::{Vector3, Point3};
 pub struct Ray {
     pt: Point3<f32>,
     dir: Vector3<f32>,
+    time: f32,
+    wavelength: Option<f32>,
 }
 
 impl Ray {
     pub fn new(pt: Point3<f32>, dir: Vector3<f32>) -> Self {
+        Self::with_time(pt, dir, 0.0)
+    }
+
+    pub fn with_time(pt: Point3<f32>, dir: Vector3<f32>, time: f32) -> Self {
+        Self::with_wavelength(pt, dir, time, None)
+    }
+
+    // Spectral Requirement
+    pub fn with_wavelength(pt: Point3<f32>, dir: Vector3<f32>, time: f32, wavelength: Option<f32>) -> Self {
         Self {
             pt,
             dir: dir.normalize(),
+            time,
+            wavelength,
         }
     }
 
@@ -28,5 +41,14 @@ impl Ray {
     pub fn dir(&self) -> Vector3<f32> {
         self.dir
     }
-}
 
+    // Moment within the camera shutter interval the ray was sent at
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    // Wavelength in nanometers of the light the ray carries, rays carrying RGB color have none
+    pub fn wavelength(&self) -> Option<f32> {
+        self.wavelength
+    }
+}
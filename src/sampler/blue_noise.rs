@@ -0,0 +1,118 @@
+use crate::sampler::{Sampler, hash, to_unit};
+use crate::sampler::sobol::scrambled_sobol;
+
+const SIZE: usize = 64;
+const SIGMA: f32 = 1.5;
+const RADIUS: i32 = 4;
+
+// Every pixel takes the same Owen-scrambled Sobol points, offset by the value of a tiled blue noise mask
+// at that pixel. Neighbouring pixels get very different offsets, so at low sample counts the remaining
+// noise is spread evenly over the image instead of clumping. The mask is tiled with a different shift
+// for every dimension.
+pub struct BlueNoise {
+    mask: Vec<f32>, // SIZE x SIZE values in [0, 1), row by row
+}
+
+impl BlueNoise {
+    pub fn new() -> Self {
+        Self {
+            mask: void_and_cluster(),
+        }
+    }
+}
+
+impl Default for BlueNoise {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sampler for BlueNoise {
+    fn sample(&self, pixel: (usize, usize), index: u32, _count: u32, dim: u32, seed: u32) -> f32 {
+        let shift = hash(&[seed, dim]) as usize;
+        let x = (pixel.0 + shift) % SIZE;
+        let y = (pixel.1 + (shift >> 16)) % SIZE;
+        let point = to_unit(scrambled_sobol(index, dim % 2, hash(&[seed, dim / 2])));
+        (point + self.mask[x + (SIZE * y)]).fract()
+    }
+}
+
+// Builds a blue noise mask with Ulichney's void and cluster method, ranking every texel by the order it
+// gets added to a pattern that always fills its largest empty space
+fn void_and_cluster() -> Vec<f32> {
+    let n = SIZE * SIZE;
+    let mut kernel: Vec<(i32, i32, f32)> = vec![];
+    for dy in -RADIUS..=RADIUS {
+        for dx in -RADIUS..=RADIUS {
+            kernel.push((dx, dy, (-((dx * dx) + (dy * dy)) as f32 / (2.0 * SIGMA * SIGMA)).exp()));
+        }
+    }
+    let splat = |energy: &mut Vec<f32>, i: usize, sign: f32| {
+        let (x, y) = ((i % SIZE) as i32, (i / SIZE) as i32);
+        for (dx, dy, w) in kernel.iter() {
+            let tx = (x + dx).rem_euclid(SIZE as i32) as usize;
+            let ty = (y + dy).rem_euclid(SIZE as i32) as usize;
+            energy[tx + (SIZE * ty)] += sign * w;
+        }
+    };
+    // Tightest cluster is the set texel with the most energy, largest void the empty texel with the least
+    let extreme = |energy: &Vec<f32>, pattern: &Vec<bool>, set: bool| -> usize {
+        let candidates = (0..n).filter(|i| pattern[*i] == set);
+        if set {
+            candidates.max_by(|a, b| energy[*a].total_cmp(&energy[*b])).unwrap()
+        } else {
+            candidates.min_by(|a, b| energy[*a].total_cmp(&energy[*b])).unwrap()
+        }
+    };
+
+    // Start from a sparse random pattern and move points from clusters into voids until it settles
+    let mut pattern = vec![false; n];
+    let mut energy = vec![0.0; n];
+    for i in (0..n).filter(|i| hash(&[*i as u32]) % 10 == 0) {
+        pattern[i] = true;
+        splat(&mut energy, i, 1.0);
+    }
+    loop {
+        let cluster = extreme(&energy, &pattern, true);
+        pattern[cluster] = false;
+        splat(&mut energy, cluster, -1.0);
+        let void = extreme(&energy, &pattern, false);
+        pattern[void] = true;
+        splat(&mut energy, void, 1.0);
+        if void == cluster {
+            break;
+        }
+    }
+
+    let mut ranks = vec![0; n];
+    let ones = pattern.iter().filter(|set| **set).count();
+    // Points of the starting pattern are ranked by removing clusters
+    let (mut shrinking, mut shrinking_energy) = (pattern.clone(), energy.clone());
+    for rank in (0..ones).rev() {
+        let cluster = extreme(&shrinking_energy, &shrinking, true);
+        shrinking[cluster] = false;
+        splat(&mut shrinking_energy, cluster, -1.0);
+        ranks[cluster] = rank;
+    }
+    // and the rest by filling voids
+    for rank in ones..n {
+        let void = extreme(&energy, &pattern, false);
+        pattern[void] = true;
+        splat(&mut energy, void, 1.0);
+        ranks[void] = rank;
+    }
+    ranks.iter().map(|rank| (*rank as f32 + 0.5) / n as f32).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mask_ranks_every_texel_once() {
+        let mut mask = BlueNoise::new().mask;
+        mask.sort_by(|a, b| a.total_cmp(b));
+        assert!(0.0 <= mask[0] && mask[SIZE * SIZE - 1] < 1.0);
+        assert!(mask.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
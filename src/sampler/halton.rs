@@ -0,0 +1,75 @@
+use crate::sampler::{Sampler, hash, to_unit};
+use crate::sampler::stratified::permute;
+
+const MAX_DIMS: usize = 256;
+
+// Halton sequence with a prime base for each dimension. Every pixel shuffles the digits of the sequence
+// differently for each dimension, which also keeps the dimensions with large bases from bunching up at
+// low sample counts. Dimensions past the first 256 fall back to independent values.
+pub struct Halton {
+    primes: Vec<u32>,
+}
+
+impl Halton {
+    pub fn new() -> Self {
+        let mut primes: Vec<u32> = vec![];
+        let mut candidate = 2;
+        while primes.len() < MAX_DIMS {
+            if primes.iter().take_while(|p| *p * *p <= candidate).all(|p| candidate % p != 0) {
+                primes.push(candidate);
+            }
+            candidate += 1;
+        }
+        Self {
+            primes,
+        }
+    }
+}
+
+impl Default for Halton {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sampler for Halton {
+    fn sample(&self, pixel: (usize, usize), index: u32, _count: u32, dim: u32, seed: u32) -> f32 {
+        match self.primes.get(dim as usize) {
+            Some(base) => scrambled_radical_inverse(index, *base, hash(&[seed, pixel.0 as u32, pixel.1 as u32, dim])),
+            None => to_unit(hash(&[seed, pixel.0 as u32, pixel.1 as u32, index, dim])),
+        }
+    }
+}
+
+// Mirrors the digits of index in the given base around the decimal point, shuffling the digits at each
+// position with a permutation picked by seed. Digits past the last one of index are shuffled zeros.
+fn scrambled_radical_inverse(mut index: u32, base: u32, seed: u32) -> f32 {
+    let inv_base = 1.0 / base as f64;
+    let mut scale = inv_base;
+    let mut result = 0.0;
+    let mut level = 0;
+    while scale > 1e-8 {
+        let digit = permute(index % base, base, hash(&[seed, level]));
+        result += digit as f64 * scale;
+        index /= base;
+        scale *= inv_base;
+        level += 1;
+    }
+    (result as f32).min(1.0 - f32::EPSILON)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The first base^k samples of a dimension land one in each of base^k equal intervals
+    #[test]
+    fn dimensions_are_stratified_by_their_base() {
+        let halton = Halton::new();
+        for (dim, count) in [(0, 16), (1, 9), (2, 25)] {
+            let mut intervals: Vec<u32> = (0..count).map(|index| (halton.sample((2, 9), index, count, dim, 11) * count as f32) as u32).collect();
+            intervals.sort();
+            assert_eq!(intervals, (0..count).collect::<Vec<u32>>());
+        }
+    }
+}
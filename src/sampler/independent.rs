@@ -0,0 +1,10 @@
+use crate::sampler::{Sampler, hash, to_unit};
+
+// Random values with no relation between samples
+pub struct Independent();
+
+impl Sampler for Independent {
+    fn sample(&self, pixel: (usize, usize), index: u32, _count: u32, dim: u32, seed: u32) -> f32 {
+        to_unit(hash(&[seed, pixel.0 as u32, pixel.1 as u32, index, dim]))
+    }
+}
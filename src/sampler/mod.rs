@@ -0,0 +1,70 @@
+pub mod independent;
+pub mod stratified;
+pub mod halton;
+pub mod sobol;
+pub mod blue_noise;
+
+// Source of the random numbers used to render a sample. Each sample of a pixel asks for values one
+// dimension at a time, with the camera using the first dimensions for the position in the pixel, the
+// point on the lens and the time, and later dimensions going to each bounce. Dimensions 2k and 2k + 1
+// form a pair that samplers spread out together over the samples of a pixel.
+//
+// Values depend only on their arguments, so a seed always renders the same image no matter what order
+// pixels are rendered in or how the work is split up.
+pub trait Sampler {
+    // Value in [0, 1) for one dimension of sample index out of count samples of a pixel
+    fn sample(&self, pixel: (usize, usize), index: u32, count: u32, dim: u32, seed: u32) -> f32;
+}
+
+// Hands out the dimensions of one sample in order
+pub struct SampleStream<'a> {
+    sampler: &'a dyn Sampler,
+    pixel: (usize, usize),
+    index: u32,
+    count: u32,
+    dim: u32,
+    seed: u32,
+}
+
+impl<'a> SampleStream<'a> {
+    pub fn new(sampler: &'a dyn Sampler, pixel: (usize, usize), index: u32, count: u32, seed: u32) -> Self {
+        Self {
+            sampler,
+            pixel,
+            index,
+            count,
+            dim: 0,
+            seed,
+        }
+    }
+
+    pub fn next_1d(&mut self) -> f32 {
+        let value = self.sampler.sample(self.pixel, self.index, self.count, self.dim, self.seed);
+        self.dim += 1;
+        value
+    }
+
+    // Starts on a new pair of dimensions so both values come from the same pair
+    pub fn next_2d(&mut self) -> (f32, f32) {
+        self.dim += self.dim % 2;
+        (self.next_1d(), self.next_1d())
+    }
+}
+
+// Mixes a list of values into a well distributed 32 bit hash
+pub fn hash(values: &[u32]) -> u32 {
+    values.iter().fold(0x9e37_79b9, |h: u32, v| mix(h ^ v.wrapping_add(0x9e37_79b9).wrapping_add(h << 6).wrapping_add(h >> 2)))
+}
+
+fn mix(mut x: u32) -> u32 {
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7feb_352d);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846c_a68b);
+    x ^ (x >> 16)
+}
+
+// Maps 32 random bits to [0, 1), keeping as many bits as f32 can hold so the result never rounds up to 1
+pub fn to_unit(bits: u32) -> f32 {
+    (bits >> 8) as f32 / (1 << 24) as f32
+}
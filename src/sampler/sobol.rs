@@ -0,0 +1,68 @@
+use crate::sampler::{Sampler, hash, to_unit};
+
+// Owen-scrambled Sobol points, using the first two Sobol dimensions for every pair of dimensions.
+// Following Burley's "Practical Hash-based Owen Scrambling", each pixel and pair of dimensions gets
+// its own scramble and its own shuffle of the sample order, so pairs stay well spread on their own
+// without lining up with each other.
+pub struct Sobol();
+
+impl Sampler for Sobol {
+    fn sample(&self, pixel: (usize, usize), index: u32, _count: u32, dim: u32, seed: u32) -> f32 {
+        let seed = hash(&[seed, pixel.0 as u32, pixel.1 as u32, dim / 2]);
+        to_unit(scrambled_sobol(index, dim % 2, seed))
+    }
+}
+
+// One of the first two dimensions of the Sobol sequence, shuffled and scrambled by seed
+pub fn scrambled_sobol(index: u32, dim: u32, seed: u32) -> u32 {
+    let shuffled = nested_uniform_scramble(index, seed);
+    nested_uniform_scramble(sobol(shuffled, dim), hash(&[seed, dim]))
+}
+
+fn sobol(mut index: u32, dim: u32) -> u32 {
+    if dim == 0 {
+        return index.reverse_bits()
+    }
+    // The second dimension's direction numbers follow Pascal's triangle mod 2
+    let mut v: u32 = 1 << 31;
+    let mut result = 0;
+    while index != 0 {
+        if index & 1 != 0 {
+            result ^= v;
+        }
+        index >>= 1;
+        v ^= v >> 1;
+    }
+    result
+}
+
+fn nested_uniform_scramble(x: u32, seed: u32) -> u32 {
+    laine_karras_permutation(x.reverse_bits(), seed).reverse_bits()
+}
+
+// Hash where each bit only depends on the bits below it, so it scrambles in base 2 from the lowest bit up
+fn laine_karras_permutation(mut x: u32, seed: u32) -> u32 {
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50_b47c);
+    x ^= x.wrapping_mul(0xb82f_1e52);
+    x ^= x.wrapping_mul(0xc7af_e638);
+    x ^= x.wrapping_mul(0x8d22_f6e6);
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 16 points of a pair fill every grid of 16 equal cells, from 1 x 16 to 16 x 1, one point per cell
+    #[test]
+    fn pairs_form_a_net() {
+        let points: Vec<(f32, f32)> = (0..16).map(|index| (Sobol().sample((4, 1), index, 16, 4, 3), Sobol().sample((4, 1), index, 16, 5, 3))).collect();
+        for (nx, ny) in [(1, 16), (2, 8), (4, 4), (8, 2), (16, 1)] {
+            let mut cells: Vec<(u32, u32)> = points.iter().map(|(x, y)| ((x * nx as f32) as u32, (y * ny as f32) as u32)).collect();
+            cells.sort();
+            cells.dedup();
+            assert_eq!(cells.len(), 16, "Points {:?} doubled up in a {} x {} grid", points, nx, ny);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::sampler::{Sampler, hash, to_unit};
+
+// Splits each pair of dimensions into a grid with a cell for every sample of a pixel and jitters each
+// sample within its own cell. Cells are shuffled differently for every pixel and pair of dimensions so
+// that pairs don't line up with each other.
+pub struct Stratified();
+
+impl Sampler for Stratified {
+    fn sample(&self, pixel: (usize, usize), index: u32, count: u32, dim: u32, seed: u32) -> f32 {
+        let nx = (count as f32).sqrt().ceil().max(1.0) as u32;
+        let ny = count.div_ceil(nx).max(1);
+        let cells = nx * ny;
+        let pair = dim / 2;
+        let cell = permute(index % cells, cells, hash(&[seed, pixel.0 as u32, pixel.1 as u32, pair]));
+        let jitter = to_unit(hash(&[seed, pixel.0 as u32, pixel.1 as u32, index, dim]));
+        if dim % 2 == 0 {
+            ((cell % nx) as f32 + jitter) / nx as f32
+        } else {
+            ((cell / nx) as f32 + jitter) / ny as f32
+        }
+    }
+}
+
+// Shuffles the values 0 to len - 1 without storing the shuffle, from Kensler's "Correlated Multi-Jittered Sampling"
+pub fn permute(mut i: u32, len: u32, seed: u32) -> u32 {
+    if len <= 1 {
+        return 0
+    }
+    let mut w = len - 1;
+    w |= w >> 1;
+    w |= w >> 2;
+    w |= w >> 4;
+    w |= w >> 8;
+    w |= w >> 16;
+    loop {
+        i ^= seed;
+        i = i.wrapping_mul(0xe170_893d);
+        i ^= seed >> 16;
+        i ^= (i & w) >> 4;
+        i ^= seed >> 8;
+        i = i.wrapping_mul(0x0929_eb3f);
+        i ^= seed >> 23;
+        i ^= (i & w) >> 1;
+        i = i.wrapping_mul(1 | (seed >> 27));
+        i = i.wrapping_mul(0x6935_fa69);
+        i ^= (i & w) >> 11;
+        i = i.wrapping_mul(0x74dc_b303);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0x9e50_1cc3);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0xc860_a3df);
+        i &= w;
+        i ^= i >> 5;
+        if i < len {
+            break;
+        }
+    }
+    i.wrapping_add(seed) % len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_stratum_gets_one_sample() {
+        for (count, (nx, ny)) in [(16, (4, 4)), (12, (4, 3))] {
+            let mut cells: Vec<(u32, u32)> = (0..count)
+                .map(|index| {
+                    let (x, y) = (Stratified().sample((3, 5), index, count, 2, 7), Stratified().sample((3, 5), index, count, 3, 7));
+                    assert!((0.0..1.0).contains(&x) && (0.0..1.0).contains(&y));
+                    ((x * nx as f32) as u32, (y * ny as f32) as u32)
+                })
+                .collect();
+            cells.sort();
+            cells.dedup();
+            assert_eq!(cells.len(), count as usize);
+        }
+    }
+
+    #[test]
+    fn permute_shuffles_every_value_once() {
+        for len in [1, 5, 16, 100] {
+            for seed in [0, 1, 0xdead_beef] {
+                let mut values: Vec<u32> = (0..len).map(|i| permute(i, len, seed)).collect();
+                values.sort();
+                assert_eq!(values, (0..len).collect::<Vec<u32>>());
+            }
+        }
+    }
+}
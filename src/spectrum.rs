@@ -0,0 +1,189 @@
+use nalgebra::{Matrix3, Vector3, Vector4};
+use std::sync::OnceLock;
+
+// Spectral Requirement
+pub const LAMBDA_MIN: f32 = 380.0; // Shortest wavelength rendered, in nanometers
+pub const LAMBDA_MAX: f32 = 720.0;
+pub const WAVELENGTHS: usize = 4; // Wavelengths carried by each path
+
+// The wavelengths a path carries light at. The first is the hero wavelength, picked uniformly over the visible
+// range, and the others are spread evenly from it, wrapping around, so together they cover the whole range and
+// a path is worth several. Surfaces that bend each wavelength differently only follow the hero wavelength,
+// after which the others are dropped (Wilkie et al.'s "Hero Wavelength Spectral Sampling").
+#[derive(Copy, Clone)]
+pub struct Wavelengths {
+    lambda: [f32; WAVELENGTHS],
+    secondary_terminated: bool,
+}
+
+impl Wavelengths {
+    // Picks the wavelengths from a number between 0 and 1
+    pub fn sample(u: f32) -> Self {
+        let mut lambda = [0.0; WAVELENGTHS];
+        for (i, l) in lambda.iter_mut().enumerate() {
+            *l = LAMBDA_MIN + ((u + (i as f32 / WAVELENGTHS as f32)).fract() * (LAMBDA_MAX - LAMBDA_MIN));
+        }
+        Self {
+            lambda,
+            secondary_terminated: false,
+        }
+    }
+
+    pub fn hero(&self) -> f32 {
+        self.lambda[0]
+    }
+
+    pub fn get_lambda(&self) -> [f32; WAVELENGTHS] {
+        self.lambda
+    }
+
+    pub fn secondary_terminated(&self) -> bool {
+        self.secondary_terminated
+    }
+
+    // Drops all but the hero wavelength, which then stands in for them
+    pub fn terminate_secondary(&mut self) {
+        self.secondary_terminated = true;
+    }
+
+    // Value of a spectrum at each wavelength
+    pub fn sample_spectrum<F: Fn(f32) -> f32>(&self, spectrum: F) -> Vector4<f32> {
+        Vector4::from_fn(|i, _| spectrum(self.lambda[i]))
+    }
+
+    // Color of light with the given values at each wavelength, on the scale the values are on
+    pub fn to_rgb(&self, values: &Vector4<f32>) -> Vector3<f32> {
+        let range = LAMBDA_MAX - LAMBDA_MIN;
+        if self.secondary_terminated {
+            return rgb_matching(self.lambda[0]) * (values[0] * range)
+        }
+        (0..WAVELENGTHS).map(|i| rgb_matching(self.lambda[i]) * values[i]).sum::<Vector3<f32>>() * (range / WAVELENGTHS as f32)
+    }
+}
+
+// Smooth spectrum made from an RGB color, which turns back into that same color. The spectrum is a mix of three
+// smooth bands covering the short, middle and long wavelengths that add up to 1 everywhere, so white and grays
+// become flat spectra. Saturated colors can need a band with a negative amount, where the spectrum is cut off at 0.
+#[derive(Copy, Clone)]
+pub struct RgbSpectrum {
+    bands: Vector3<f32>, // Amount of the long, middle and short bands
+}
+
+impl RgbSpectrum {
+    // From a color on the same scale spectrum values are wanted on, so 0 to 1 for the fraction of light a
+    // surface carries on and 0 to 255 or more for light given off
+    pub fn new(rgb: &Vector3<f32>) -> Self {
+        Self {
+            bands: conversion().1 * rgb,
+        }
+    }
+
+    pub fn at(&self, lambda: f32) -> f32 {
+        bands(lambda).dot(&self.bands).max(0.0)
+    }
+
+    // Values at each of the wavelengths
+    pub fn sample(&self, wavelengths: &Wavelengths) -> Vector4<f32> {
+        wavelengths.sample_spectrum(|lambda| self.at(lambda))
+    }
+}
+
+// Long, middle and short bands
+fn bands(lambda: f32) -> Vector3<f32> {
+    let long = smoothstep(575.0, 605.0, lambda);
+    let short = 1.0 - smoothstep(485.0, 515.0, lambda);
+    Vector3::new(long, 1.0 - long - short, short)
+}
+
+fn smoothstep(from: f32, to: f32, x: f32) -> f32 {
+    let t = ((x - from) / (to - from)).clamp(0.0, 1.0);
+    t * t * (3.0 - (2.0 * t))
+}
+
+// CIE 1931 color matching functions, using the piecewise gaussian fit from Wyman et al.'s "Simple Analytic
+// Approximations to the CIE XYZ Color Matching Functions"
+pub fn xyz_matching(lambda: f32) -> Vector3<f32> {
+    let g = |mu: f32, below: f32, above: f32| {
+        let sigma = if lambda < mu { below } else { above };
+        (-0.5 * ((lambda - mu) / sigma).powi(2)).exp()
+    };
+    Vector3::new(
+        (1.056 * g(599.8, 37.9, 31.0)) + (0.362 * g(442.0, 16.0, 26.7)) - (0.065 * g(501.1, 20.4, 26.2)),
+        (0.821 * g(568.8, 46.9, 40.5)) + (0.286 * g(530.9, 16.3, 31.1)),
+        (1.217 * g(437.0, 11.8, 36.0)) + (0.681 * g(459.0, 26.0, 13.8)),
+    )
+}
+
+// Linear RGB per unit of light at a wavelength, scaled so that a flat spectrum of 1 over the rendered range
+// turns into a color of 1 in every channel
+fn rgb_matching(lambda: f32) -> Vector3<f32> {
+    conversion().0 * xyz_matching(lambda)
+}
+
+// Matrices turning color matching function values into RGB, and RGB colors into band amounts
+fn conversion() -> &'static (Matrix3<f32>, Matrix3<f32>) {
+    static CONVERSION: OnceLock<(Matrix3<f32>, Matrix3<f32>)> = OnceLock::new();
+    CONVERSION.get_or_init(|| {
+        let xyz_to_rgb = Matrix3::new(
+            3.2406, -1.5372, -0.4986,
+            -0.9689, 1.8758, 0.0415,
+            0.0557, -0.2040, 1.0570,
+        );
+        // Integrated a nanometer at a time
+        let steps = (LAMBDA_MAX - LAMBDA_MIN) as usize;
+        let lambdas = (0..steps).map(|i| LAMBDA_MIN + i as f32 + 0.5);
+        let white = lambdas.clone().map(|lambda| xyz_to_rgb * xyz_matching(lambda)).sum::<Vector3<f32>>();
+        let to_rgb = Matrix3::from_diagonal(&white.map(|c| 1.0 / c)) * xyz_to_rgb;
+        let band_colors = lambdas.map(|lambda| (to_rgb * xyz_matching(lambda)) * bands(lambda).transpose()).sum::<Matrix3<f32>>();
+        let to_bands = band_colors.try_inverse().expect("Spectral bands must give independent colors");
+        (to_rgb, to_bands)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wavelengths_cover_the_range_evenly() {
+        let wavelengths = Wavelengths::sample(0.9);
+        let mut lambda = wavelengths.get_lambda();
+        assert_eq!(wavelengths.hero(), lambda[0]);
+        lambda.sort_by(|a, b| a.total_cmp(b));
+        assert!(LAMBDA_MIN <= lambda[0] && lambda[WAVELENGTHS - 1] < LAMBDA_MAX);
+        let spacing = (LAMBDA_MAX - LAMBDA_MIN) / WAVELENGTHS as f32;
+        assert!(lambda.windows(2).all(|pair| (pair[1] - pair[0] - spacing).abs() < 0.01));
+    }
+
+    // Averaging over wavelengths picked across the range turns a color's spectrum back into the color
+    #[test]
+    fn rgb_spectrum_round_trips() {
+        for rgb in [Vector3::new(1.0, 1.0, 1.0), Vector3::new(0.8, 0.3, 0.1), Vector3::new(0.2, 0.5, 0.7)] {
+            let spectrum = RgbSpectrum::new(&rgb);
+            let steps = 2000;
+            let color = (0..steps)
+                .map(|i| {
+                    let wavelengths = Wavelengths::sample((i as f32 + 0.5) / steps as f32);
+                    wavelengths.to_rgb(&spectrum.sample(&wavelengths))
+                })
+                .sum::<Vector3<f32>>() / steps as f32;
+            assert!((color - rgb).amax() < 0.02, "{:?} came back as {:?}", rgb, color);
+        }
+    }
+
+    // Dropping the secondary wavelengths gives noisier colors that still average out the same
+    #[test]
+    fn hero_wavelength_alone_round_trips() {
+        let rgb = Vector3::new(0.8, 0.3, 0.1);
+        let spectrum = RgbSpectrum::new(&rgb);
+        let steps = 2000;
+        let color = (0..steps)
+            .map(|i| {
+                let mut wavelengths = Wavelengths::sample((i as f32 + 0.5) / steps as f32);
+                wavelengths.terminate_secondary();
+                wavelengths.to_rgb(&spectrum.sample(&wavelengths))
+            })
+            .sum::<Vector3<f32>>() / steps as f32;
+        assert!((color - rgb).amax() < 0.02, "{:?} came back as {:?}", rgb, color);
+    }
+}
@@ -1,24 +1,61 @@
-use nalgebra::{Point3, Vector3};
+use nalgebra::{Point3, Vector3, Unit, UnitQuaternion};
 use crate::camera::Camera;
+use crate::camera::projection::{Projection, CubeFace};
 use crate::objects::Object;
-use crate::ppmhandler::PPMImage;
+use crate::objects::motion::{Motion, Posed};
+use crate::camera::path::CameraPath;
+use crate::film::Film;
+use crate::ppmhandler::{PPMHandler, PPMImage, PPMType};
 use crate::ray::Ray;
 use crate::materials::Material;
-use std::collections::HashMap;
+use crate::media::{Medium, MediumEvent};
+use crate::media::phase::HenyeyGreenstein;
+use crate::sampler::{Sampler, SampleStream, hash};
+use crate::sampler::independent::Independent;
+use crate::integrators::Integrator;
+use crate::integrators::recursive::Recursive;
+use crate::integrators::photon::PhotonMapper;
+use crate::integrators::metropolis::Metropolis;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
 
 const TOL: f32 = 0.001; // TODO: maybe abstract or something?
+const TILE_SIZE: usize = 32; // Pixels along each side of the tiles images are rendered in
+
+// Object at a position, with the material of its surface or the medium filling it
+type Placed<T> = (Point3<f32>, Box<dyn Object>, Box<T>);
+
+type Background = Box<dyn Fn(&Ray) -> Vector3<u8>>;
 
 pub struct World {
     cameras: HashMap<String, (Point3<f32>, Camera)>,
-    objects: HashMap<String, (Point3<f32>, Box<dyn Object>, Box<dyn Material>)>,
-    background_color: Box<dyn Fn(&Ray) -> Vector3<u8>>,
+    // Objects and media are kept sorted by key so they are always gone through in the same order, which keeps
+    // ties between hits and the samples media take the same from one run to the next
+    objects: BTreeMap<String, Placed<dyn Material>>,
+    motions: HashMap<String, Motion>, // Keyed by object key, objects without one stay still
+    lights: Vec<String>, // Sorted keys of objects with a material that gives off light, which sample_light picks from
+    media: BTreeMap<String, Placed<dyn Medium>>, // Media fill the inside of their boundary object
+    fog: Option<(f32, Box<dyn Medium>)>, // Medium filling the whole world up to a distance from each ray origin
+    camera_paths: HashMap<String, CameraPath>, // Keyed by camera key, only used when rendering frames
+    sampler: Box<dyn Sampler>,
+    seed: u32, // Renders with the same seed come out identical
+    integrator: Box<dyn Integrator>, // Used by renders that aren't given one
+    background_color: Background,
 }
 
 impl World {
-    pub fn new(background_color: Box<dyn Fn(&Ray) -> Vector3<u8>>) -> Self {
+    pub fn new(background_color: Background) -> Self {
         Self {
             cameras: HashMap::new(),
-            objects: HashMap::new(),
+            objects: BTreeMap::new(),
+            motions: HashMap::new(),
+            lights: vec![],
+            media: BTreeMap::new(),
+            fog: None,
+            camera_paths: HashMap::new(),
+            sampler: Box::new(Independent()),
+            seed: 0,
+            integrator: Box::new(Recursive()),
             background_color,
         }
     }
@@ -28,85 +65,387 @@ impl World {
     }
 
     pub fn add_object(&mut self, object: Box<dyn Object>, pos: Point3<f32>, material: Box<dyn Material>, key: String) {
+        self.motions.remove(&key);
+        self.update_lights(&key, (&pos, object.as_ref()), material.as_ref());
+        self.objects.insert(key, (pos, object, material));
+    }
+
+    // Motion Blur Requirement
+    pub fn add_moving_object(&mut self, object: Box<dyn Object>, pos: Point3<f32>, motion: Motion, material: Box<dyn Material>, key: String) {
+        self.motions.insert(key.clone(), motion);
+        self.update_lights(&key, (&pos, object.as_ref()), material.as_ref());
         self.objects.insert(key, (pos, object, material));
     }
 
+    // Keeps the sorted light keys in step with the objects added. Only lights with a surface that can be
+    // sampled are picked from, others are still seen when rays happen to hit them.
+    fn update_lights(&mut self, key: &String, (pos, object): (&Point3<f32>, &dyn Object), material: &dyn Material) {
+        let light = material.is_light() && object.sample_surface(pos, (0.5, 0.5)).is_some();
+        match (self.lights.binary_search(key), light) {
+            (Err(index), true) => self.lights.insert(index, key.clone()),
+            (Ok(index), false) => { self.lights.remove(index); },
+            _ => (),
+        }
+    }
+
+    pub fn add_medium(&mut self, boundary: Box<dyn Object>, pos: Point3<f32>, medium: Box<dyn Medium>, key: String) {
+        self.media.insert(key, (pos, boundary, medium));
+    }
+
+    // Rays that escape the scene travel through at most extent units of fog
+    pub fn set_fog(&mut self, medium: Box<dyn Medium>, extent: f32) {
+        self.fog = Some((extent, medium));
+    }
+
+    pub fn clear_fog(&mut self) {
+        self.fog = None;
+    }
+
+    // Sampling Requirement
+    pub fn set_sampler(&mut self, sampler: Box<dyn Sampler>) {
+        self.sampler = sampler;
+    }
+
+    // Seeding Requirement
+    pub fn set_seed(&mut self, seed: u32) {
+        self.seed = seed;
+    }
+
+    pub fn get_seed(&self) -> u32 {
+        self.seed
+    }
+
+    // Integrator Requirement
+    pub fn set_integrator(&mut self, integrator: Box<dyn Integrator>) {
+        self.integrator = integrator;
+    }
+
     pub fn render(&self, key: String, max_bounces: u8, rays_per_pixel: u32) -> PPMImage {
+        self.render_with(key, self.integrator.as_ref(), max_bounces, rays_per_pixel)
+    }
+
+    // Renders with the given integrator instead of the world's own
+    pub fn render_with(&self, key: String, integrator: &dyn Integrator, max_bounces: u8, rays_per_pixel: u32) -> PPMImage {
+        let entry = self.cameras.get(&key);
+        match entry {
+            Some((pos, camera)) => self.render_camera(pos, camera, integrator, max_bounces, rays_per_pixel, self.seed),
+            None => panic!("Camera not found in world"),
+        }
+    }
+
+    // Panoramic Requirement
+    // Renders all six faces of a cube map around a camera, keeping its orientation and image width
+    pub fn render_cube_map(&self, key: String, max_bounces: u8, rays_per_pixel: u32) -> Vec<(CubeFace, PPMImage)> {
         let entry = self.cameras.get(&key);
         match entry {
-            Some((pos, camera)) => {
-                let colors: Vec<(Vector3<u8>, usize, usize)> = camera.get_rays(pos, rays_per_pixel)
-                    .iter()
-                    .map(|(ray, x, y)| (self.ray_color(ray, 0, max_bounces), *x, *y))
-                    .collect();
-                let mut image: PPMImage = PPMImage::new(camera.get_iwidth() as usize, camera.get_iheight() as usize, 255);
-                let mut counts: Vec<Vec<u32>> = vec![vec![0; camera.get_iheight()]; camera.get_iwidth()];
-                for (color, x, y) in colors.iter() {
-                    if counts[*x][*y] == 0 {
-                        image.change_pixel(*x, *y, color.try_cast::<u32>().unwrap());
-                    } else {
-                        image.change_pixel(*x, *y, (color.try_cast::<u32>().unwrap() + (image.pixel_at(*x, *y) * counts[*x][*y])) / (counts[*x][*y] + 1));
+            Some((pos, camera)) => CubeFace::all().iter()
+                .map(|face| (*face, self.render_camera(pos, &camera.with_cube_face(*face), self.integrator.as_ref(), max_bounces, rays_per_pixel, self.seed)))
+                .collect(),
+            None => panic!("Camera not found in world"),
+        }
+    }
+
+    // Animation Requirement
+    pub fn set_camera_path(&mut self, path: CameraPath, key: String) {
+        if !self.cameras.contains_key(&key) {
+            panic!("Camera key not found while setting camera path");
+        }
+        self.camera_paths.insert(key, path);
+    }
+
+    pub fn clear_camera_path(&mut self, key: String) {
+        self.camera_paths.remove(&key);
+    }
+
+    // Renders frames at frame_rate frames per unit of time into numbered files starting with prefix, returning
+    // their paths. Each frame gets its own seed made from the world's seed. Cameras follow their paths, and shutters open relative to the start of each frame so moving
+    // objects animate with the same times used for motion blur. The world itself is left unchanged.
+    pub fn render_frames(&self, key: String, frames: Range<u32>, frame_rate: f32, max_bounces: u8, rays_per_pixel: u32, prefix: String) -> std::io::Result<Vec<String>> {
+        if frame_rate <= 0.0 {
+            panic!("Frame rate must be positive, given frame rate was {}", frame_rate)
+        }
+        let handler = PPMHandler();
+        let mut paths: Vec<String> = vec![];
+        for frame in frames {
+            let time = frame as f32 / frame_rate;
+            let (pos, camera) = self.camera_at(&key, time);
+            let image = self.render_camera(&pos, &camera, self.integrator.as_ref(), max_bounces, rays_per_pixel, hash(&[self.seed, frame]));
+            paths.push(handler.write(&image, PPMType::P6, format!("{}{:04}", prefix, frame))?);
+        }
+        Ok(paths)
+    }
+
+    // Returns the position and settings of a camera at a time along its path
+    fn camera_at(&self, key: &String, time: f32) -> (Point3<f32>, Camera) {
+        let (mut pos, mut camera) = match self.cameras.get(key) {
+            Some((pos, camera)) => (*pos, camera.clone()),
+            None => panic!("Camera not found in world"),
+        };
+        if let Some(path) = self.camera_paths.get(key) {
+            let keyframe = path.keyframe_at(time);
+            keyframe.apply(&mut camera);
+            pos = keyframe.pos;
+        }
+        let (open, close) = camera.get_shutter();
+        camera.set_shutter(time + open, time + close);
+        (pos, camera)
+    }
+
+    // Traces the image tile by tile and each pixel's rays one at a time, so memory use doesn't grow with the sample count
+    fn render_camera(&self, pos: &Point3<f32>, camera: &Camera, integrator: &dyn Integrator, max_bounces: u8, rays_per_pixel: u32, seed: u32) -> PPMImage {
+        let mut film = Film::new(camera.get_iwidth(), camera.get_iheight());
+        for (columns, rows) in camera.get_tiles(TILE_SIZE) {
+            for y in rows {
+                for x in columns.clone() {
+                    for (ray, mut samples) in camera.get_pixel_rays(pos, (x, y), rays_per_pixel, self.sampler.as_ref(), seed) {
+                        self.add_sample(&mut film, (x, y), (pos, camera), integrator, &ray, &mut samples, max_bounces);
                     }
-                    counts[*x][*y] += 1;
                 }
-                image
-            },
-            None => panic!("Camera not found in world"),
+            }
+        }
+        film.to_image()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn add_sample(&self, film: &mut Film, (x, y): (usize, usize), camera: (&Point3<f32>, &Camera), integrator: &dyn Integrator, ray: &Ray, samples: &mut SampleStream, max_bounces: u8) {
+        let (color, splats) = integrator.color_with_splats(self, camera, ray, samples, max_bounces);
+        film.add_sample(x, y, color);
+        for ((sx, sy), splat) in splats {
+            film.add_splat(sx, sy, splat);
         }
     }
 
-    fn ray_color(&self, ray: &Ray, num_bounces: u8, max_bounces: u8) -> Vector3<u8> {
-        if num_bounces >= max_bounces {
-            return (self.background_color)(ray)
+    // Adaptive Sampling Requirement
+    // Renders min_samples rays for every pixel, then keeps doubling the samples of pixels whose error is
+    // above target_error until they reach max_samples. The error is half the width of the 95% confidence
+    // interval of a pixel's brightness on the 0 to 255 scale. Call to_image on the result for the image,
+    // or count_image to see where the samples went.
+    pub fn render_adaptive(&self, key: String, max_bounces: u8, min_samples: u32, max_samples: u32, target_error: f32) -> Film {
+        if min_samples < 2 || max_samples < min_samples {
+            panic!("Adaptive sampling needs at least 2 samples per pixel and no fewer than the minimum at most, given {} to {}", min_samples, max_samples)
         }
-        let intersection = self.intersection(ray);
-        match intersection {
-            Some((point, key)) => {
-                let entry = self.objects.get(&key);
-                match entry {
-                    Some((pos, obj, mat)) => {
-                        let bounce = mat.bounce(ray, obj, pos, &point);
-                        (((1.0 - mat.reflectance()) * mat.color().cast::<f32>()) + 
-                        ((self.ray_color(&bounce, num_bounces + 1, max_bounces).cast::<f32>() * mat.reflectance())))
-                        .try_cast::<u8>().unwrap()
-                    },
-                    None => panic!("Object key not found while computing ray color"),
+        let (pos, camera) = match self.cameras.get(&key) {
+            Some(entry) => entry,
+            None => panic!("Camera not found in world"),
+        };
+        let (width, height) = (camera.get_iwidth(), camera.get_iheight());
+        let mut film = Film::new(width, height);
+        // Index of the next sample of each pixel, pixels are done once it reaches max_samples
+        let mut next: Vec<Vec<u32>> = vec![vec![0; height]; width];
+        loop {
+            let mut active = false;
+            for (x, column) in next.iter_mut().enumerate() {
+                for (y, start) in column.iter_mut().enumerate() {
+                    // Pixels the projection doesn't cover get no samples in the first pass and are skipped
+                    if *start >= max_samples || (*start > 0 && (film.count(x, y) == 0 || film.error(x, y) <= target_error)) {
+                        continue;
+                    }
+                    active = true;
+                    let end = if *start == 0 { min_samples } else { 2 * *start }.min(max_samples);
+                    for index in *start..end {
+                        if let Some((ray, mut samples)) = camera.get_ray(pos, (x, y), index, max_samples, self.sampler.as_ref(), self.seed) {
+                            self.add_sample(&mut film, (x, y), (pos, camera), self.integrator.as_ref(), &ray, &mut samples, max_bounces);
+                        }
+                    }
+                    *start = end;
                 }
-            },
-            None => (self.background_color)(ray),
+            }
+            if !active {
+                return film
+            }
+        }
+    }
+
+    // Progressive Requirement
+    // Renders up to passes passes of samples_per_pass rays for every pixel into one film, calling on_pass with
+    // the number of finished passes and the film after each of them. Rendering stops early once on_pass
+    // returns false. Every pass is spread over its pixels on its own, so stopping after any pass leaves an
+    // evenly sampled image. Call to_image on the film for the current image.
+    pub fn render_progressive<F: FnMut(u32, &Film) -> bool>(&self, key: String, max_bounces: u8, samples_per_pass: u32, passes: u32, mut on_pass: F) -> Film {
+        if samples_per_pass == 0 {
+            panic!("Progressive rendering needs at least 1 sample per pass")
+        }
+        let (pos, camera) = match self.cameras.get(&key) {
+            Some(entry) => entry,
+            None => panic!("Camera not found in world"),
+        };
+        let mut film = Film::new(camera.get_iwidth(), camera.get_iheight());
+        for pass in 0..passes {
+            self.render_pass(&mut film, (pos, camera), self.integrator.as_ref(), max_bounces, (pass * samples_per_pass)..((pass + 1) * samples_per_pass), passes * samples_per_pass);
+            if !on_pass(pass + 1, &film) {
+                break;
+            }
+        }
+        film
+    }
+
+    // Progressive Photon Mapping Requirement
+    // Renders like render_progressive with a photon map of photons_per_pass photons built for every pass. The
+    // radius photons are gathered from starts at radius and shrinks every pass, more slowly the closer alpha is
+    // to 1, so the blur of the photon map fades away as passes are added while each pass stays as noisy as the
+    // last (Knaus and Zwicker's "Progressive Photon Mapping: A Probabilistic Approach"). Alpha around 2/3 works well.
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_progressive_photons<F: FnMut(u32, &Film) -> bool>(&self, key: String, max_bounces: u8, photons_per_pass: u32, radius: f32, alpha: f32, samples_per_pass: u32, passes: u32, mut on_pass: F) -> Film {
+        if samples_per_pass == 0 {
+            panic!("Progressive rendering needs at least 1 sample per pass")
+        }
+        if alpha <= 0.0 || 1.0 <= alpha {
+            panic!("Alpha must be a number between 0 and 1, given alpha was {}", alpha)
+        }
+        let (pos, camera) = match self.cameras.get(&key) {
+            Some(entry) => entry,
+            None => panic!("Camera not found in world"),
+        };
+        let mut film = Film::new(camera.get_iwidth(), camera.get_iheight());
+        let mut radius = radius;
+        for pass in 0..passes {
+            let mapper = PhotonMapper::with_seed(self, photons_per_pass, radius, max_bounces, hash(&[self.seed, pass]));
+            self.render_pass(&mut film, (pos, camera), &mapper, max_bounces, (pass * samples_per_pass)..((pass + 1) * samples_per_pass), passes * samples_per_pass);
+            if !on_pass(pass + 1, &film) {
+                break;
+            }
+            radius *= ((pass as f32 + 1.0 + alpha) / (pass as f32 + 2.0)).sqrt();
+        }
+        film
+    }
+
+    // Metropolis Requirement
+    // Renders with chains of paths exploring around the bright paths they find, see Metropolis
+    pub fn render_metropolis(&self, key: String, metropolis: &Metropolis, max_bounces: u8) -> PPMImage {
+        match self.cameras.get(&key) {
+            Some((pos, camera)) => metropolis.render(self, (pos, camera), max_bounces, self.seed),
+            None => panic!("Camera not found in world"),
         }
     }
 
-    fn intersection(&self, ray: &Ray) -> Option<(Point3<f32>, String)> {
-        let mut intersections: Vec<(Point3<f32>, String)> = vec![];
-        for key in self.objects.keys() {
-            let entry = self.objects.get(key);
-            match entry {
-                Some((pos, obj, _)) => {
-                    let obj_intersection = obj.intersection_point(pos, ray, TOL);
-                    match obj_intersection {
-                        Some(point) => intersections.push((point, key.to_string())),
-                        None => { },
+    // Adds the rays with sample indices out of count samples for every pixel to film. Passes take their indices
+    // after those of earlier passes out of the samples of all passes, so stratified samplers don't put every
+    // pass in the same strata.
+    fn render_pass(&self, film: &mut Film, (pos, camera): (&Point3<f32>, &Camera), integrator: &dyn Integrator, max_bounces: u8, indices: Range<u32>, count: u32) {
+        for x in 0..camera.get_iwidth() {
+            for y in 0..camera.get_iheight() {
+                for index in indices.clone() {
+                    if let Some((ray, mut samples)) = camera.get_ray(pos, (x, y), index, count, self.sampler.as_ref(), self.seed) {
+                        self.add_sample(film, (x, y), (pos, camera), integrator, &ray, &mut samples, max_bounces);
                     }
-                },
-                None => { },
+                }
             }
         }
-        if intersections.len() == 0 {
-            None
-        } else {
-            let mut closest: (Point3<f32>, String) = intersections[0].clone();
-            for intersection in intersections {
-                if (ray.pos() - intersection.0).magnitude() < (ray.pos() - closest.0).magnitude() {
-                    closest = intersection;
+    }
+
+    // Integrator Requirement
+    // Color the background shows along a ray, on the same scale as colors
+    pub fn background(&self, ray: &Ray) -> Vector3<f32> {
+        (self.background_color)(ray).cast::<f32>()
+    }
+
+    // Returns the closest interaction with any medium before the ray reaches t_surface
+    pub fn medium_event(&self, ray: &Ray, t_surface: f32, samples: &mut SampleStream) -> Option<(MediumEvent, HenyeyGreenstein)> {
+        let mut closest: Option<(MediumEvent, HenyeyGreenstein)> = None;
+        let mut consider = |event: Option<MediumEvent>, phase: HenyeyGreenstein| {
+            if let Some(event) = event {
+                if closest.as_ref().map_or(true, |(prev, _)| event.t < prev.t) {
+                    closest = Some((event, phase));
                 }
             }
-            Some(closest)
+        };
+        for (pos, boundary, medium) in self.media.values() {
+            for (enter, exit) in boundary.intersection_intervals(pos, ray) {
+                let (t_min, t_max) = (enter.max(TOL), exit.min(t_surface));
+                if t_min < t_max {
+                    consider(medium.sample_event(pos, ray, t_min, t_max, samples), medium.phase());
+                }
+            }
+        }
+        if let Some((extent, medium)) = &self.fog {
+            consider(medium.sample_event(&ray.pos(), ray, TOL, t_surface.min(*extent), samples), medium.phase());
+        }
+        closest
+    }
+
+    // Returns the closest surface the ray hits
+    pub fn hit(&self, ray: &Ray) -> Option<Hit<'_>> {
+        let mut closest: Option<Hit<'_>> = None;
+        for (key, (pos, obj, mat)) in self.objects.iter() {
+            let motion = self.motions.get(key);
+            let obj_intersection = match motion {
+                Some(motion) => Posed::new(obj.as_ref(), motion, ray.time()).intersection_point(pos, ray, TOL),
+                None => obj.intersection_point(pos, ray, TOL),
+            };
+            if let Some(point) = obj_intersection {
+                let t = (point - ray.pos()).magnitude();
+                if closest.as_ref().map_or(true, |hit| t < hit.t) {
+                    closest = Some(Hit { point, t, material: mat.as_ref(), object: obj.as_ref(), pos, motion, time: ray.time() });
+                }
+            }
+        }
+        closest
+    }
+
+    // Whether nothing blocks the straight line between two points at a time
+    pub fn visible(&self, from: &Point3<f32>, to: &Point3<f32>, time: f32) -> bool {
+        let distance = (to - from).magnitude();
+        self.hit(&Ray::with_time(*from, to - from, time)).map_or(true, |hit| hit.t >= distance * (1.0 - TOL))
+    }
+
+    // Fraction of light that makes it through the media along a ray up to t_max, estimated using samples
+    pub fn transmittance(&self, ray: &Ray, t_max: f32, samples: &mut SampleStream) -> f32 {
+        let mut transmittance = 1.0;
+        for (pos, boundary, medium) in self.media.values() {
+            for (enter, exit) in boundary.intersection_intervals(pos, ray) {
+                let (t_min, t_end) = (enter.max(TOL), exit.min(t_max));
+                if t_min < t_end {
+                    transmittance *= medium.transmittance(pos, ray, t_min, t_end, samples);
+                }
+            }
+        }
+        if let Some((extent, medium)) = &self.fog {
+            let t_end = t_max.min(*extent);
+            if TOL < t_end {
+                transmittance *= medium.transmittance(&ray.pos(), ray, TOL, t_end, samples);
+            }
+        }
+        transmittance
+    }
+
+    // Fraction of light that makes it along the straight line between two points at a time, 0 if a surface blocks it
+    pub fn transmittance_between(&self, from: &Point3<f32>, to: &Point3<f32>, time: f32, samples: &mut SampleStream) -> f32 {
+        if !self.visible(from, to, time) {
+            return 0.0
+        }
+        self.transmittance(&Ray::with_time(*from, to - from, time), (to - from).magnitude(), samples)
+    }
+
+    // Picks a point on a light, which is any object with a light material and a surface that can
+    // be sampled. Returns None when the world has no lights.
+    pub fn sample_light(&self, time: f32, samples: &mut SampleStream) -> Option<LightSample> {
+        if self.lights.is_empty() {
+            return None
+        }
+        let choice = ((samples.next_1d() * self.lights.len() as f32) as usize).min(self.lights.len() - 1);
+        let key = &self.lights[choice];
+        let (pos, obj, mat) = match self.objects.get(key) {
+            Some(entry) => entry,
+            None => panic!("Light key not found in world objects"),
+        };
+        let u = samples.next_2d();
+        let (point, normal, pdf) = match self.motions.get(key) {
+            Some(motion) => Posed::new(obj.as_ref(), motion, time).sample_surface(pos, u),
+            None => obj.sample_surface(pos, u),
+        }?;
+        Some(LightSample { point, normal, emitted: mat.emitted(), pdf: pdf / self.lights.len() as f32 })
+    }
+
+    // Probability density per unit of area of sample_light picking the point of a hit, 0 if it isn't on a light
+    pub fn light_pdf(&self, hit: &Hit) -> f32 {
+        if !hit.material.is_light() || self.lights.is_empty() {
+            return 0.0
         }
+        hit.surface_pdf() / self.lights.len() as f32
     }
 
     // Camera Requirement
-    pub fn move_camera_to(&mut self, new_pos: Point3<f32>, key: String) -> () {
+    pub fn move_camera_to(&mut self, new_pos: Point3<f32>, key: String) {
         if let Some((pos, _)) = self.cameras.get_mut(&key) {
             *pos = new_pos;
         } else {
@@ -114,11 +453,164 @@ impl World {
         }
     }
 
-    pub fn make_camera_lookat(&mut self, target: Point3<f32>, key: String) -> () {
+    pub fn make_camera_lookat(&mut self, target: Point3<f32>, key: String) {
         if let Some((pos, camera)) = self.cameras.get_mut(&key) {
             camera.lookat(target, *pos);
         } else {
             panic!("Camera key not found while moving camera");
         }
     }
+
+    // Orientation Requirement
+    pub fn make_camera_lookat_with_up(&mut self, target: Point3<f32>, up: Vector3<f32>, roll: f32, key: String) {
+        if let Some((pos, camera)) = self.cameras.get_mut(&key) {
+            camera.lookat_with_up(target, *pos, up, roll);
+        } else {
+            panic!("Camera key not found while moving camera");
+        }
+    }
+
+    pub fn set_camera_yaw_pitch_roll(&mut self, yaw: f32, pitch: f32, roll: f32, key: String) {
+        if let Some((_, camera)) = self.cameras.get_mut(&key) {
+            camera.set_yaw_pitch_roll(yaw, pitch, roll);
+        } else {
+            panic!("Camera key not found while moving camera");
+        }
+    }
+
+    // Swings a camera around target by yaw radians about the world y axis and pitch radians up over it,
+    // keeping it pointed at target. The camera keeps its roll and can pass over the top of target.
+    pub fn orbit_camera(&mut self, target: Point3<f32>, yaw: f32, pitch: f32, key: String) {
+        if let Some((pos, camera)) = self.cameras.get_mut(&key) {
+            let rotation = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), yaw)
+                * UnitQuaternion::from_axis_angle(&Unit::new_normalize(camera.get_right()), -pitch);
+            *pos = target + (rotation * (*pos - target));
+            camera.rotate(rotation);
+            camera.lookat_with_up(target, *pos, camera.get_up(), 0.0);
+        } else {
+            panic!("Camera key not found while moving camera");
+        }
+    }
+
+    // Moves a camera forward along its view direction, or backward for negative distances
+    pub fn dolly_camera(&mut self, distance: f32, key: String) {
+        if let Some((pos, camera)) = self.cameras.get_mut(&key) {
+            *pos += distance * camera.get_dir();
+        } else {
+            panic!("Camera key not found while moving camera");
+        }
+    }
+
+    // Slides a camera sideways along its right vector and vertically along its up vector
+    pub fn pan_camera(&mut self, right: f32, up: f32, key: String) {
+        if let Some((pos, camera)) = self.cameras.get_mut(&key) {
+            *pos += (right * camera.get_right()) + (up * camera.get_up());
+        } else {
+            panic!("Camera key not found while moving camera");
+        }
+    }
+
+    // Physical Camera Requirement
+    // Focuses a camera on whatever is seen at the center of a pixel, returning the new focus distance
+    // or None if the pixel only sees the background
+    pub fn autofocus_camera(&mut self, x: usize, y: usize, key: String) -> Option<f32> {
+        let (pos, camera) = match self.cameras.get(&key) {
+            Some(entry) => entry,
+            None => panic!("Camera key not found while focusing camera"),
+        };
+        let ray = camera.focus_ray(pos, x, y)?;
+        let point = self.hit(&ray)?.point;
+        // Perspective cameras focus on a plane, so the distance is measured along the view direction
+        let distance = match camera.get_projection() {
+            Projection::Perspective => (point - ray.pos()).dot(&camera.get_dir()),
+            _ => (point - ray.pos()).magnitude(),
+        };
+        if let Some((_, camera)) = self.cameras.get_mut(&key) {
+            camera.set_focus_distance(distance);
+        }
+        Some(distance)
+    }
+}
+
+// Closest surface a ray hits, with what's needed to shade it
+pub struct Hit<'a> {
+    pub point: Point3<f32>,
+    pub t: f32, // Distance along the ray
+    pub material: &'a dyn Material,
+    object: &'a dyn Object,
+    pos: &'a Point3<f32>,
+    motion: Option<&'a Motion>,
+    time: f32,
+}
+
+impl<'a> Hit<'a> {
+    // Outward surface normal at the hit
+    pub fn normal(&self) -> Vector3<f32> {
+        match self.motion {
+            Some(motion) => Posed::new(self.object, motion, self.time).surface_normal(self.pos, &self.point),
+            None => self.object.surface_normal(self.pos, &self.point),
+        }.normalize()
+    }
+
+    fn surface_pdf(&self) -> f32 {
+        match self.motion {
+            Some(motion) => Posed::new(self.object, motion, self.time).surface_pdf(self.pos, &self.point),
+            None => self.object.surface_pdf(self.pos, &self.point),
+        }
+    }
+
+    // Ray leaving the surface after the material bounces the ray that hit it
+    pub fn bounce(&self, ray: &Ray, samples: &mut SampleStream) -> Ray {
+        match self.motion {
+            Some(motion) => self.material.bounce(ray, &Posed::new(self.object, motion, self.time), self.pos, &self.point, samples),
+            None => self.material.bounce(ray, self.object, self.pos, &self.point, samples),
+        }
+    }
+}
+
+// Point picked on a light, with the light it gives off and the probability density of picking it per unit of area
+pub struct LightSample {
+    pub point: Point3<f32>,
+    pub normal: Vector3<f32>,
+    pub emitted: Vector3<f32>,
+    pub pdf: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrators::path::PathTracer;
+    use crate::materials::{diffuse::Diffuse, emissive::Emissive};
+    use crate::media::homogeneous::Homogeneous;
+    use crate::objects::{plane::Plane, sphere::Sphere};
+
+    fn scene(seed: u32) -> World {
+        let mut world = World::new(Box::new(|_ray: &Ray| Vector3::new(20, 20, 30)));
+        world.add_camera(Camera::new(Vector3::new(0.0, 1.0, 0.0), Vector3::new(1.0, 0.0, 0.0), 16, 0.873, 16.0 / 9.0, 3.0, 0.0), Point3::new(0.0, 0.0, -0.5), "camera".to_string());
+        world.add_object(Box::new(Sphere::new(0.3)), Point3::new(0.5, 0.8, -3.0), Box::new(Emissive::new(Vector3::new(255, 255, 255), 10.0)), "light".to_string());
+        world.add_object(Box::new(Sphere::new(0.4)), Point3::new(-0.4, 0.0, -3.0), Box::new(Diffuse::new(Vector3::new(200, 80, 80), 0.5)), "ball".to_string());
+        world.add_object(Box::new(Plane::new(Vector3::new(0.0, 1.0, 0.0))), Point3::new(0.0, -0.5, 0.0), Box::new(Diffuse::new(Vector3::new(150, 150, 150), 0.5)), "floor".to_string());
+        world.add_medium(Box::new(Sphere::new(0.4)), Point3::new(0.4, 0.0, -3.0), Box::new(Homogeneous::new(2.0, 0.5, Vector3::new(230, 230, 230), HenyeyGreenstein::isotropic())), "smoke".to_string());
+        world.set_seed(seed);
+        world
+    }
+
+    fn pixels(image: &PPMImage) -> Vec<Vector3<u32>> {
+        let (width, height) = image.dim();
+        (0..width).flat_map(|x| (0..height).map(move |y| image.pixel_at(x, y))).collect()
+    }
+
+    #[test]
+    fn same_seed_renders_same_image() {
+        let first = scene(7).render_with("camera".to_string(), &PathTracer::new(2), 4, 4);
+        let second = scene(7).render_with("camera".to_string(), &PathTracer::new(2), 4, 4);
+        assert_eq!(pixels(&first), pixels(&second));
+    }
+
+    #[test]
+    fn different_seed_renders_different_image() {
+        let first = scene(7).render_with("camera".to_string(), &PathTracer::new(2), 4, 4);
+        let second = scene(8).render_with("camera".to_string(), &PathTracer::new(2), 4, 4);
+        assert_ne!(pixels(&first), pixels(&second));
+    }
 }